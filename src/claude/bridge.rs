@@ -80,3 +80,9 @@ pub async fn run_claude_async(prompt: &str, config: &ClaudeConfig) -> Result<()>
 pub fn is_claude_available() -> bool {
     which::which("claude").is_ok()
 }
+
+/// Where the configured `claude.binary` resolves to, `None` when it is
+/// missing or not executable
+pub fn resolve_binary(binary: &std::path::Path) -> Option<std::path::PathBuf> {
+    which::which(binary).ok()
+}
@@ -1,5 +1,7 @@
 //! Claude CLI bridge
 
 mod bridge;
+mod verdict;
 
 pub use bridge::*;
+pub use verdict::*;
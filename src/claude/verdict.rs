@@ -0,0 +1,79 @@
+use std::fmt;
+
+/// Outcome of a blocking validation, as answered by `templates/validate.md`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Warn(String),
+    Fail(String),
+}
+
+impl Verdict {
+    /// Read the verdict from Claude's response: the first line that is
+    /// `PASS`, `WARN: <reason>` or `FAIL: <reason>`, ignoring markdown
+    /// around it. A response without one counts as a warning.
+    pub fn parse(response: &str) -> Self {
+        for line in response.lines() {
+            let line = line.trim().trim_matches(|c| c == '`' || c == '*').trim();
+
+            if line.eq_ignore_ascii_case("PASS") {
+                return Verdict::Pass;
+            }
+            if let Some(reason) = strip_label(line, "WARN") {
+                return Verdict::Warn(reason);
+            }
+            if let Some(reason) = strip_label(line, "FAIL") {
+                return Verdict::Fail(reason);
+            }
+        }
+
+        Verdict::Warn("no PASS/WARN/FAIL verdict in Claude's response".to_string())
+    }
+
+    /// Exit code for the hook: only a failure stops git
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Verdict::Fail(_) => 1,
+            _ => 0,
+        }
+    }
+
+    /// The more severe of two verdicts, keeping the first on a tie
+    pub fn worst(self, other: Verdict) -> Verdict {
+        if other.severity() > self.severity() {
+            other
+        } else {
+            self
+        }
+    }
+
+    fn severity(&self) -> u8 {
+        match self {
+            Verdict::Pass => 0,
+            Verdict::Warn(_) => 1,
+            Verdict::Fail(_) => 2,
+        }
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "✅ PASS"),
+            Verdict::Warn(reason) => write!(f, "⚠️  WARN: {}", reason),
+            Verdict::Fail(reason) => write!(f, "❌ FAIL: {}", reason),
+        }
+    }
+}
+
+/// `reason` from `LABEL: reason`, case-insensitively
+fn strip_label(line: &str, label: &str) -> Option<String> {
+    let head = line.get(..label.len())?;
+    if !head.eq_ignore_ascii_case(label) {
+        return None;
+    }
+
+    let rest = line[label.len()..].trim_start();
+    let reason = rest.strip_prefix(':')?.trim();
+    Some(reason.to_string())
+}
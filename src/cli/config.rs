@@ -1,21 +1,240 @@
 use anyhow::Result;
+use dialoguer::{theme::ColorfulTheme, Confirm};
+use std::path::{Path, PathBuf};
 
-pub async fn run(global: bool, edit: bool) -> Result<()> {
-    let config_path = if global {
-        // TODO: Get actual XDG path
-        "~/.config/gitclaude/config.toml"
-    } else {
-        ".gitclaude/config.toml"
-    };
+use crate::cli::editor::open_in_editor;
+use crate::config::{self, Severity};
+use crate::ConfigActions;
+
+pub async fn run(global: bool, edit: bool, origin: bool, action: Option<ConfigActions>) -> Result<()> {
+    match action {
+        Some(ConfigActions::Validate { strict }) => return validate(global, strict),
+        Some(ConfigActions::Get { key }) => return get(global, &key),
+        Some(ConfigActions::Schema) => {
+            println!("{}", serde_json::to_string_pretty(&config::config_schema())?);
+            return Ok(());
+        }
+        Some(ConfigActions::Set { key, value }) => {
+            let path = target_path(global)?;
+            return update(&path, |content| config::set_value(content, &key, config::parse_value(&value)));
+        }
+        Some(ConfigActions::Migrate { dry_run }) => return migrate(&target_path(global)?, dry_run),
+        Some(ConfigActions::Unset { key }) => {
+            let path = target_path(global)?;
+            return update(&path, |content| config::unset_value(content, &key));
+        }
+        None => {}
+    }
 
     if edit {
-        // TODO: Open in $EDITOR
-        println!("Opening {} in editor...", config_path);
+        return edit_config(&target_path(global)?);
+    }
+
+    show(global, origin)
+}
+
+/// Config file targeted by edits: the global file or the repo file
+fn target_path(global: bool) -> Result<PathBuf> {
+    if global {
+        config::global_config_path()
+    } else {
+        config::repo_config_path()
+    }
+}
+
+/// Print the effective value of a dotted key
+fn get(global_only: bool, key: &str) -> Result<()> {
+    let global = config::load_global_config()?;
+    let repo = if global_only { None } else { config::load_repo_config()? };
+    let config = config::merge_configs(global, repo, &config::load_overrides()?)?.resolved()?;
+    let value = toml::Value::try_from(&config)?;
+
+    match config::get_value(&value, key) {
+        Some(toml::Value::Table(table)) => print!("{}", toml::to_string_pretty(table)?),
+        Some(toml::Value::String(s)) => println!("{}", s),
+        Some(other) => println!("{}", other),
+        None => anyhow::bail!("Key `{}` is not set", key),
+    }
+
+    Ok(())
+}
+
+/// Rewrite a config file in place, refusing changes that make it invalid
+fn update(path: &Path, edit: impl FnOnce(&str) -> Result<String>) -> Result<()> {
+    let content = if path.exists() {
+        std::fs::read_to_string(path)?
     } else {
-        // TODO: Print current config
-        println!("Configuration: {}", config_path);
+        // Stamp new files so later migrations know their layout
+        format!("version = {}\n", config::CONFIG_VERSION)
+    };
+
+    let updated = edit(&content)?;
+    let diagnostics = config::validate_file(path, &updated, None);
+
+    for diagnostic in &diagnostics {
+        println!("{}", diagnostic);
+    }
+
+    if diagnostics.iter().any(|d| d.severity == Severity::Error) {
+        anyhow::bail!("Not saving {}: the change makes it invalid", path.display());
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, updated)?;
+
+    println!("✅ Updated {}", path.display());
+
+    Ok(())
+}
+
+/// Migrate a config file to the current layout, showing a diff first
+fn migrate(path: &Path, dry_run: bool) -> Result<()> {
+    if !path.exists() {
+        anyhow::bail!("{} does not exist", path.display());
+    }
+
+    let content = std::fs::read_to_string(path)?;
+    let migrated = config::migrate(&content)?;
+
+    if migrated.applied.is_empty() {
+        println!("✅ {} is already at version {}", path.display(), config::CONFIG_VERSION);
+        return Ok(());
+    }
+
+    println!("Migrating {} from version {} to {}:", path.display(), migrated.from, config::CONFIG_VERSION);
+    for description in &migrated.applied {
+        println!("  - {}", description);
+    }
+    println!();
+
+    let display_path = path.display().to_string();
+    let diff = similar::TextDiff::from_lines(&content, &migrated.content);
+    print!("{}", diff.unified_diff().header(&display_path, &display_path));
+
+    if dry_run {
         println!();
-        println!("# TODO: Print config contents");
+        println!("Dry run, nothing written.");
+        return Ok(());
+    }
+
+    std::fs::write(path, &migrated.content)?;
+    println!();
+    println!("✅ Updated {}", path.display());
+
+    Ok(())
+}
+
+/// Print the effective configuration
+fn show(global_only: bool, origin: bool) -> Result<()> {
+    let global = config::load_global_config()?;
+    let repo = if global_only { None } else { config::load_repo_config()? };
+    let config = config::merge_configs(global.clone(), repo.clone(), &config::load_overrides()?)?;
+
+    println!("# Global: {}", config::global_config_path()?.display());
+    if !global_only {
+        if let Ok(path) = config::repo_config_path() {
+            println!("# Repo:   {}", path.display());
+        }
+    }
+    println!();
+
+    if !origin {
+        print!("{}", toml::to_string_pretty(&config.resolved()?)?);
+        return Ok(());
+    }
+
+    let traced = config::trace_origins(&config, global.as_ref(), repo.as_ref())?;
+    let width = traced.iter().map(|t| t.key.len()).max().unwrap_or(0);
+
+    for value in traced {
+        let origins: Vec<String> = value.origins.iter().map(|o| o.to_string()).collect();
+        println!(
+            "{:width$} = {}  # {}",
+            value.key,
+            value.value,
+            origins.join(" + "),
+            width = width
+        );
+    }
+
+    Ok(())
+}
+
+/// Open a config file in the editor and re-validate it afterwards
+fn edit_config(path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    if !path.exists() {
+        std::fs::write(path, format!("# gitclaude configuration\nversion = {}\n", config::CONFIG_VERSION))?;
+    }
+
+    loop {
+        open_in_editor(path)?;
+
+        let content = std::fs::read_to_string(path)?;
+        let merged = config::load_config().ok();
+        let diagnostics = config::validate_file(path, &content, merged.as_ref());
+
+        for diagnostic in &diagnostics {
+            println!("{}", diagnostic);
+        }
+
+        if !diagnostics.iter().any(|d| d.severity == Severity::Error) {
+            println!("✅ Saved {}", path.display());
+            return Ok(());
+        }
+
+        let reopen = Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt("Configuration has errors. Re-open editor?")
+            .default(true)
+            .interact()?;
+
+        if !reopen {
+            anyhow::bail!("Configuration is invalid");
+        }
+    }
+}
+
+/// Validate the global config and, unless `global_only`, the repo config
+fn validate(global_only: bool, strict: bool) -> Result<()> {
+    let mut paths = vec![config::global_config_path()?];
+    if !global_only {
+        if let Ok(path) = config::repo_config_path() {
+            paths.push(path);
+        }
+    }
+
+    // References are resolved against the merged config when it loads
+    let merged = config::load_config().ok();
+
+    let mut errors = 0;
+    let mut warnings = 0;
+
+    for path in paths.iter().filter(|p| p.exists()) {
+        let content = std::fs::read_to_string(path)?;
+
+        for diagnostic in config::validate_file(path, &content, merged.as_ref()) {
+            match diagnostic.severity {
+                Severity::Error => errors += 1,
+                Severity::Warning => warnings += 1,
+            }
+            println!("{}", diagnostic);
+        }
+    }
+
+    if errors == 0 && warnings == 0 {
+        println!("✅ Configuration is valid");
+        return Ok(());
+    }
+
+    println!();
+    println!("{} error(s), {} warning(s)", errors, warnings);
+
+    if errors > 0 || (strict && warnings > 0) {
+        anyhow::bail!("Configuration is invalid");
     }
 
     Ok(())
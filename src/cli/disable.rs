@@ -1,13 +1,43 @@
 use anyhow::Result;
 
-pub async fn run(keep_config: bool) -> Result<()> {
-    // TODO: Implement disable logic
-    // 1. Remove hooks
-    // 2. Optionally remove .gitclaude/
+use crate::config;
+use crate::hooks::{self, HookChange};
+
+pub async fn run(keep_config: bool, purge: bool) -> Result<()> {
+    let repo_root = config::find_repo_root()?;
+
+    if let hooks::IntegrationMode::Manager(manager) = hooks::integration_mode(&repo_root)? {
+        if hooks::unregister_from_manager(manager, &repo_root)? {
+            println!("   Removed gitclaude steps from {}", manager.config_path(&repo_root).display());
+        }
+    }
+
+    let events = hooks::installed_hooks(&repo_root)?;
+
+    for (event, change) in hooks::remove_hooks(&repo_root, &events)? {
+        match change {
+            HookChange::Restored => println!("   {}: original hook restored", event),
+            HookChange::Skipped => println!("   {}: hook was modified, left in place", event),
+            _ => println!("   {}: hook removed", event),
+        }
+    }
+
+    hooks::set_enabled(&repo_root, false)?;
+    if let Err(e) = hooks::unregister_repo(&repo_root) {
+        tracing::warn!("Could not unregister the repo: {:#}", e);
+    }
 
     println!("✅ Gitclaude disabled in this repository");
 
-    if !keep_config {
+    let gitclaude_dir = repo_root.join(".gitclaude");
+    let config_path = gitclaude_dir.join("config.toml");
+    if purge && gitclaude_dir.exists() {
+        std::fs::remove_dir_all(&gitclaude_dir)?;
+        println!("   Removed {}", gitclaude_dir.display());
+    } else if !keep_config && config_path.exists() {
+        // Templates, responses and includes may be hand-written, keep them
+        std::fs::remove_file(&config_path)?;
+        let _ = std::fs::remove_dir(&gitclaude_dir);
         println!("   Configuration removed");
     } else {
         println!("   Configuration preserved");
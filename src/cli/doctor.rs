@@ -0,0 +1,452 @@
+use anyhow::Result;
+use std::path::Path;
+
+use crate::claude;
+use crate::config::{self, Config, Severity};
+use crate::hooks;
+use crate::output;
+use crate::rate_limit;
+use crate::templates;
+
+/// Result of one check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Ok,
+    Warn,
+    Fail,
+}
+
+struct Check {
+    status: Status,
+    name: String,
+    detail: String,
+    /// What to do about a warning or failure
+    fix: Option<String>,
+}
+
+impl Check {
+    fn ok(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            status: Status::Ok,
+            name: name.into(),
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    fn warn(name: impl Into<String>, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            status: Status::Warn,
+            name: name.into(),
+            detail: detail.into(),
+            fix: Some(fix.into()),
+        }
+    }
+
+    fn fail(name: impl Into<String>, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            status: Status::Fail,
+            name: name.into(),
+            detail: detail.into(),
+            fix: Some(fix.into()),
+        }
+    }
+
+    fn print(&self) {
+        let icon = match self.status {
+            Status::Ok => "✅",
+            Status::Warn => "⚠️ ",
+            Status::Fail => "❌",
+        };
+        println!("{} {}: {}", icon, self.name, self.detail);
+        if let Some(fix) = &self.fix {
+            println!("   → {}", fix);
+        }
+    }
+}
+
+/// Check everything a hook needs to reach Claude and deliver the response
+pub async fn run() -> Result<()> {
+    println!("🩺 Gitclaude Doctor");
+    println!("─────────────────────");
+
+    let mut checks = vec![];
+    let config = check_config(&mut checks);
+
+    if let Some(config) = &config {
+        checks.push(check_claude(config));
+    }
+    checks.push(check_gitclaude_on_path());
+
+    match config::find_repo_root() {
+        Ok(repo_root) => {
+            checks.extend(check_hooks_path(&repo_root));
+            if let Some(config) = &config {
+                if let Some(reason) = hooks::excluded_reason(&config.global, &repo_root, true) {
+                    checks.push(Check::warn(
+                        "Repo filter",
+                        format!("hooks skip this repo: {}", reason),
+                        "Adjust `global.allow_repos` / `global.ignore_repos` in the global config",
+                    ));
+                }
+                checks.extend(check_hooks(&repo_root, config));
+            }
+            checks.extend(check_rate_limit(&repo_root));
+        }
+        Err(_) => checks.push(Check::warn(
+            "Repository",
+            "not in a git repository, hook checks skipped",
+            "Run `gitclaude doctor` inside the repository whose hooks misbehave",
+        )),
+    }
+
+    if let Some(config) = &config {
+        checks.extend(check_templates(config));
+        checks.extend(check_notifications(config));
+    }
+
+    if std::env::var("GITCLAUDE_SKIP").is_ok_and(|v| v == "1") {
+        checks.push(Check::warn(
+            "GITCLAUDE_SKIP",
+            "set to 1, every hook is a no-op",
+            "Unset it with `unset GITCLAUDE_SKIP`",
+        ));
+    }
+
+    for check in &checks {
+        check.print();
+    }
+
+    let failed = checks.iter().filter(|c| c.status == Status::Fail).count();
+    let warned = checks.iter().filter(|c| c.status == Status::Warn).count();
+    println!();
+    if failed == 0 && warned == 0 {
+        println!("✅ All checks passed");
+        return Ok(());
+    }
+    println!("{} problem(s), {} warning(s)", failed, warned);
+
+    if failed > 0 {
+        anyhow::bail!("{} check(s) failed", failed);
+    }
+    Ok(())
+}
+
+/// Parse and validate each config layer, returning the merged config if it
+/// loads
+fn check_config(checks: &mut Vec<Check>) -> Option<Config> {
+    let loaded = config::load_config();
+
+    let layers = [
+        ("Global config", config::global_config_path().ok()),
+        ("Repo config", config::repo_config_path().ok()),
+    ];
+    for (name, path) in layers {
+        let Some(path) = path else { continue };
+
+        if !path.exists() {
+            if name == "Global config" {
+                checks.push(Check::warn(
+                    name,
+                    format!("{} does not exist, defaults apply", path.display()),
+                    "Run `gitclaude init` to create it",
+                ));
+            } else {
+                checks.push(Check::ok(name, "none, the global config applies"));
+            }
+            continue;
+        }
+
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) => {
+                checks.push(Check::fail(
+                    name,
+                    format!("{} can't be read: {}", path.display(), e),
+                    "Check the file's permissions",
+                ));
+                continue;
+            }
+        };
+
+        let diagnostics = config::validate_file(&path, &content, loaded.as_ref().ok());
+        let errors: Vec<_> = diagnostics.iter().filter(|d| d.severity == Severity::Error).collect();
+        let warnings: Vec<_> = diagnostics.iter().filter(|d| d.severity == Severity::Warning).collect();
+
+        let check = match (errors.first(), warnings.first()) {
+            (Some(first), _) => Check::fail(
+                name,
+                format!("{} error(s), first: {}", errors.len(), first),
+                "Fix the problems listed by `gitclaude config validate`",
+            ),
+            (None, Some(first)) => Check::warn(
+                name,
+                format!("{} warning(s), first: {}", warnings.len(), first),
+                "See `gitclaude config validate`",
+            ),
+            (None, None) => Check::ok(name, path.display().to_string()),
+        };
+        checks.push(check);
+    }
+
+    match loaded {
+        Ok(config) => Some(config),
+        Err(e) => {
+            checks.push(Check::fail(
+                "Config",
+                format!("does not load: {:#}", e),
+                "Every hook fails until this is fixed, see `gitclaude config validate`",
+            ));
+            None
+        }
+    }
+}
+
+fn check_claude(config: &Config) -> Check {
+    match &config.claude.binary {
+        Some(binary) => match claude::resolve_binary(binary) {
+            Some(path) => Check::ok("Claude CLI", format!("{} (claude.binary)", path.display())),
+            None => Check::fail(
+                "Claude CLI",
+                format!("claude.binary = {} is missing or not executable", binary.display()),
+                "Point `claude.binary` at the claude executable, e.g. the output of `which claude`",
+            ),
+        },
+        None if claude::is_claude_available() => {
+            let path = which::which("claude").map(|p| p.display().to_string()).unwrap_or_default();
+            Check::ok("Claude CLI", format!("{} (from PATH)", path))
+        }
+        None => Check::fail(
+            "Claude CLI",
+            "`claude` is not on PATH",
+            "Install Claude Code, or set `claude.binary` to its absolute path",
+        ),
+    }
+}
+
+/// Hook scripts fall back to PATH when the gitclaude they were generated
+/// with is gone, and hook manager steps always use it
+fn check_gitclaude_on_path() -> Check {
+    match which::which("gitclaude") {
+        Ok(path) => Check::ok("gitclaude on PATH", path.display().to_string()),
+        Err(_) => {
+            let current = std::env::current_exe()
+                .ok()
+                .and_then(|exe| exe.parent().map(|dir| dir.display().to_string()))
+                .unwrap_or_default();
+            Check::warn(
+                "gitclaude on PATH",
+                "not found; hook manager steps and hooks whose binary moved skip every event",
+                format!("Add {} to PATH", current),
+            )
+        }
+    }
+}
+
+/// Whether `core.hooksPath` sends git past the hooks gitclaude installed
+fn check_hooks_path(repo_root: &Path) -> Vec<Check> {
+    let name = "core.hooksPath";
+    let Ok(repo) = git2::Repository::open(repo_root) else { return vec![] };
+    let Ok(git_config) = repo.config() else { return vec![] };
+
+    let Ok(entry) = git_config.get_entry(name) else {
+        return vec![Check::ok(name, "not set, git runs hooks from the git dir")];
+    };
+    let value = entry.value().unwrap_or("").to_string();
+    let shared = !matches!(entry.level(), git2::ConfigLevel::Local | git2::ConfigLevel::App);
+
+    let default_dir = config::git_common_dir(repo_root).map(|dir| dir.join("hooks"));
+    let bypassed = default_dir
+        .as_ref()
+        .ok()
+        .filter(|dir| hooks::hooks_dir(repo_root).is_ok_and(|active| &active != *dir))
+        .and_then(|dir| hooks::gitclaude_hooks_in(dir).ok())
+        .unwrap_or_default();
+
+    let check = if !bypassed.is_empty() {
+        Check::fail(
+            name,
+            format!("set to {}, so git never runs the gitclaude hooks ({}) in the git dir", value, bypassed.join(", ")),
+            "Run `gitclaude enable` to install them where hooksPath points, or `git config --unset core.hooksPath`",
+        )
+    } else if shared {
+        Check::warn(
+            name,
+            format!("set to {} outside this repo, hooks there are shared with other repos", value),
+            "Make sure that is intended, or override it here with `git config --local core.hooksPath`",
+        )
+    } else {
+        Check::ok(name, format!("{} (hooks are installed there)", value))
+    };
+    vec![check]
+}
+
+/// The hook of each enabled event is installed and can run
+fn check_hooks(repo_root: &Path, config: &Config) -> Vec<Check> {
+    let mut events: Vec<String> = config
+        .events
+        .iter()
+        .filter(|(_, e)| e.enabled)
+        .map(|(name, _)| name.clone())
+        .collect();
+    events.sort();
+
+    if events.is_empty() {
+        return vec![Check::warn(
+            "Hooks",
+            "no events are enabled",
+            "Enable some with `gitclaude enable -e post-commit`",
+        )];
+    }
+
+    match hooks::integration_mode(repo_root) {
+        Ok(hooks::IntegrationMode::Manager(manager)) => {
+            return vec![Check::ok(
+                "Hooks",
+                format!("run through {} ({})", manager, manager.config_path(repo_root).display()),
+            )];
+        }
+        Ok(_) => {}
+        Err(e) => return vec![Check::fail("Hooks", format!("{:#}", e), "Check that the repository opens with `git status`")],
+    }
+
+    let hooks_dir = match hooks::hooks_dir(repo_root) {
+        Ok(dir) => dir,
+        Err(e) => return vec![Check::fail("Hooks", format!("{:#}", e), "Check that the repository opens with `git status`")],
+    };
+
+    config
+        .hook_names(&events)
+        .into_iter()
+        .map(|hook| {
+            let name = format!("Hook {}", hook);
+            let path = hooks_dir.join(&hook);
+
+            match std::fs::read_to_string(&path) {
+                Err(_) => Check::fail(name, format!("not installed in {}", hooks_dir.display()), "Run `gitclaude enable`"),
+                Ok(content) if !hooks::is_gitclaude_hook(&content) => Check::fail(
+                    name,
+                    format!("{} doesn't run gitclaude", path.display()),
+                    "Run `gitclaude enable`, which keeps the existing hook and runs it first",
+                ),
+                Ok(_) if !hooks::is_executable(&path) => Check::fail(
+                    name,
+                    format!("{} is not executable, git skips it", path.display()),
+                    format!("Run `chmod +x {}`", path.display()),
+                ),
+                Ok(content) if hooks::is_stale_hook(&content) => {
+                    let stamp = hooks::read_hook_stamp(&content);
+                    let detail = match &stamp.binary {
+                        Some(binary) if stamp.version >= hooks::HOOK_SCRIPT_VERSION => {
+                            format!("calls {} instead of this gitclaude", binary.display())
+                        }
+                        _ => format!(
+                            "script version {} is older than {}",
+                            stamp.version,
+                            hooks::HOOK_SCRIPT_VERSION
+                        ),
+                    };
+                    Check::warn(name, detail, "Run `gitclaude hooks upgrade`")
+                }
+                Ok(_) => Check::ok(name, path.display().to_string()),
+            }
+        })
+        .collect()
+}
+
+/// Every template an enabled event or action uses resolves and compiles
+fn check_templates(config: &Config) -> Vec<Check> {
+    let mut events: Vec<&String> = config.events.iter().filter(|(_, e)| e.enabled).map(|(name, _)| name).collect();
+    events.sort();
+
+    let mut checks = vec![];
+    for event in events {
+        let config = match config.clone().with_profile(event, None) {
+            Ok(config) => config,
+            Err(e) => {
+                checks.push(Check::fail(
+                    format!("Templates {}", event),
+                    format!("{:#}", e),
+                    "Fix the profile the event selects",
+                ));
+                continue;
+            }
+        };
+        let event_config = config.event(event);
+
+        let names: Vec<&str> = if event_config.actions.is_empty() {
+            vec![event_config.template.as_str()]
+        } else {
+            event_config.actions.iter().map(|a| a.template.as_str()).collect()
+        };
+
+        let failures: Vec<String> = names
+            .iter()
+            .filter_map(|name| templates::check_template(name, &config.templates).err())
+            .map(|e| format!("{:#}", e))
+            .collect();
+
+        checks.push(if failures.is_empty() {
+            Check::ok(format!("Templates {}", event), names.join(", "))
+        } else {
+            Check::fail(
+                format!("Templates {}", event),
+                failures.join("; "),
+                format!(
+                    "Create the template in {} or pick a built-in one: {}",
+                    config.templates.directory.display(),
+                    templates::BUILTIN_TEMPLATES.join(", ")
+                ),
+            )
+        });
+    }
+    checks
+}
+
+fn check_rate_limit(repo_root: &Path) -> Vec<Check> {
+    let name = "Rate-limit state";
+    let files = match rate_limit::check_state_files(repo_root) {
+        Ok(files) => files,
+        Err(e) => return vec![Check::fail(name, format!("{:#}", e), "Check the permissions of the git dir")],
+    };
+
+    let broken: Vec<Check> = files
+        .iter()
+        .filter_map(|(path, result)| result.as_ref().err().map(|e| (path, e)))
+        .map(|(path, e)| {
+            Check::fail(
+                name,
+                format!("{} can't be read: {:#}", path.display(), e),
+                format!("Delete {}, it is recreated on the next run", path.display()),
+            )
+        })
+        .collect();
+
+    if !broken.is_empty() {
+        return broken;
+    }
+    vec![Check::ok(name, format!("{} file(s) readable", files.len()))]
+}
+
+/// Only matters when an enabled event notifies
+fn check_notifications(config: &Config) -> Vec<Check> {
+    let notifies = config.general.notify
+        && config.events.iter().filter(|(_, e)| e.enabled).any(|(name, _)| {
+            let event = config.event(name);
+            event.output.iter().any(|o| o == "notify")
+                || event.actions.iter().any(|a| a.output.as_ref().unwrap_or(&event.output).iter().any(|o| o == "notify"))
+        });
+    if !notifies {
+        return vec![];
+    }
+
+    let name = "Notifications";
+    vec![match output::notification_server() {
+        Ok(server) => Check::ok(name, server),
+        Err(e) => Check::fail(
+            name,
+            format!("no notification daemon answers: {}", e),
+            "Start one (e.g. dunst or mako), or use another output such as `file` instead of `notify`",
+        ),
+    }]
+}
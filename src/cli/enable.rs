@@ -1,17 +1,92 @@
 use anyhow::Result;
+use dialoguer::{theme::ColorfulTheme, Confirm};
+use std::path::Path;
+
+use crate::config::{self, CONFIG_VERSION};
+use crate::hooks::{self, HookChange};
 
 pub async fn run(events: Option<Vec<String>>) -> Result<()> {
-    // TODO: Implement enable logic
-    // 1. Check if in git repo
-    // 2. Install hooks
-    // 3. Create .gitclaude/config.toml if needed
+    let repo_root = config::find_repo_root()?;
+    let config_path = config::repo_config_path()?;
 
-    println!("✅ Gitclaude enabled in this repository");
+    let events = match events {
+        Some(events) => events,
+        None => {
+            let config = config::load_config()?;
+            let mut enabled: Vec<String> = config
+                .events
+                .iter()
+                .filter(|(_, e)| e.enabled)
+                .map(|(name, _)| name.clone())
+                .collect();
+            enabled.sort();
+
+            if enabled.is_empty() {
+                vec!["post-commit".to_string()]
+            } else {
+                enabled
+            }
+        }
+    };
+
+    let hook_names = config::load_config()?.hook_names(&events);
+
+    let manager = hooks::detect_hook_manager(&repo_root).filter(|manager| {
+        let prompt = format!(
+            "This repo uses {}. Register gitclaude there instead of in .git/hooks?",
+            manager
+        );
 
-    if let Some(events) = events {
-        println!("   Events: {}", events.join(", "));
+        // Without a terminal to ask on, going through the framework is the safe choice
+        !console::user_attended()
+            || Confirm::with_theme(&ColorfulTheme::default())
+                .with_prompt(prompt)
+                .default(true)
+                .interact()
+                .unwrap_or(true)
+    });
+
+    if let Some(manager) = manager {
+        let path = hooks::register_with_manager(manager, &repo_root, &hook_names)?;
+        println!("   Registered with {} in {}", manager, path.display());
+        if let Some(hint) = manager.install_hint(&hook_names) {
+            println!("   Run `{}` to activate the hooks", hint);
+        }
     } else {
-        println!("   Events: post-commit (default)");
+        install_git_hooks(&repo_root, &hook_names)?;
+        if let Err(e) = hooks::register_repo(&repo_root) {
+            tracing::warn!("Could not register the repo for `gitclaude hooks upgrade --all`: {:#}", e);
+        }
+    }
+
+    hooks::set_enabled(&repo_root, true)?;
+
+    if !config_path.exists() {
+        let mut content = format!("version = {}\n", CONFIG_VERSION);
+        for event in &events {
+            content.push_str(&format!("\n[events.{}]\nenabled = true\n", event));
+        }
+
+        std::fs::create_dir_all(config_path.parent().unwrap_or(&repo_root))?;
+        std::fs::write(&config_path, content)?;
+        println!("   Created {}", config_path.display());
+    }
+
+    println!("✅ Gitclaude enabled in this repository");
+    println!("   Events: {}", events.join(", "));
+
+    Ok(())
+}
+
+fn install_git_hooks(repo_root: &Path, events: &[String]) -> Result<()> {
+    for (event, change) in hooks::install_hooks(repo_root, events)? {
+        match change {
+            HookChange::Chained => {
+                println!("   {}: existing hook kept and runs before gitclaude", event)
+            }
+            HookChange::Updated => println!("   {}: hook updated", event),
+            _ => println!("   {}: hook installed", event),
+        }
     }
 
     Ok(())
@@ -0,0 +1,56 @@
+use anyhow::Result;
+
+use crate::config;
+use crate::hooks;
+use crate::HookActions;
+
+pub async fn run(action: HookActions) -> Result<()> {
+    match action {
+        HookActions::Upgrade { all } => upgrade(all),
+    }
+}
+
+/// Regenerate stale hook scripts in this repo, or in every registered one
+fn upgrade(all: bool) -> Result<()> {
+    let repos = if all {
+        hooks::registered_repos()?
+    } else {
+        vec![config::find_repo_root()?]
+    };
+
+    if repos.is_empty() {
+        println!("No registered repositories, `gitclaude enable` registers a repo");
+        return Ok(());
+    }
+
+    println!("🔄 Upgrading hooks to version {}", hooks::HOOK_SCRIPT_VERSION);
+
+    let mut failed = 0;
+    for repo in repos {
+        if !repo.join(".git").exists() {
+            hooks::unregister_repo(&repo)?;
+            println!("   {}: no longer a git repository, removed from the registry", repo.display());
+            continue;
+        }
+
+        match hooks::upgrade_hooks(&repo) {
+            Ok(upgraded) if upgraded.is_empty() => println!("   {}: up to date", repo.display()),
+            Ok(upgraded) => println!("   {}: upgraded {}", repo.display(), upgraded.join(", ")),
+            Err(e) => {
+                eprintln!("❌ {}: {:#}", repo.display(), e);
+                failed += 1;
+                continue;
+            }
+        }
+
+        // Repos enabled before the registry existed get registered here
+        if !all && !hooks::installed_hooks(&repo)?.is_empty() {
+            hooks::register_repo(&repo)?;
+        }
+    }
+
+    if failed > 0 {
+        anyhow::bail!("{} repo(s) could not be upgraded", failed);
+    }
+    Ok(())
+}
@@ -37,12 +37,13 @@ pub async fn run(force: bool) -> Result<()> {
         .with_prompt("Which git events should trigger Claude?")
         .items(&[
             "post-commit (after each commit)",
-            "post-push (after push to remote)",
+            "pre-push (changelog when pushing)",
             "pre-commit (validation before commit)",
             "post-merge (after merge)",
             "post-checkout (after branch switch)",
+            "prepare-commit-msg (draft commit messages)",
         ])
-        .defaults(&[true, false, false, false, false])
+        .defaults(&[true, false, false, false, false, false])
         .interact()?;
 
     // Step 3: Sync vs async
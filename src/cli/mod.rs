@@ -2,7 +2,11 @@ pub mod init;
 pub mod enable;
 pub mod disable;
 pub mod config;
+pub mod editor;
 pub mod status;
+pub mod doctor;
 pub mod logs;
 pub mod run;
+pub mod release_notes;
 pub mod templates;
+pub mod hooks;
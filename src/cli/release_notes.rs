@@ -0,0 +1,14 @@
+use anyhow::{bail, Result};
+
+use crate::config;
+
+/// Run the tag event by hand for an existing tag
+pub async fn run(tag: &str, dry_run: bool, profile: Option<&str>) -> Result<()> {
+    let repo_root = config::find_repo_root()?;
+    let repo = git2::Repository::open(&repo_root)?;
+    if repo.find_reference(&format!("refs/tags/{}", tag)).is_err() {
+        bail!("Tag '{}' not found", tag);
+    }
+
+    super::run::run("tag", dry_run, profile, false, false, false, &[tag.to_string()]).await
+}
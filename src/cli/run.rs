@@ -1,31 +1,629 @@
-use anyhow::Result;
+use anyhow::{Context as _, Result};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
 
-pub async fn run(event: &str, dry_run: bool) -> Result<()> {
-    println!("🚀 Manuellt triggar event: {}", event);
+use crate::claude::{self, ClaudeResponse, Verdict};
+use crate::config::{self, Config, EventConfig};
+use crate::context::{self, Context, ContextLevel, HookInput};
+use crate::hooks;
+use crate::output;
+use crate::rate_limit::{self, RateLimitDecision};
+use crate::templates;
+
+/// How long a deferred pre-push run waits for the push to land
+const PUSH_WAIT: Duration = Duration::from_secs(300);
+
+/// Environment variable that makes hooks skip gitclaude entirely
+const SKIP_ENV: &str = "GITCLAUDE_SKIP";
+
+/// A blocking run ended in a FAIL verdict
+#[derive(Debug, thiserror::Error)]
+#[error("blocked: {0}")]
+struct Blocked(Verdict);
+
+pub async fn run(
+    event: &str,
+    dry_run: bool,
+    profile: Option<&str>,
+    from_hook: bool,
+    deferred: bool,
+    blocking: bool,
+    args: &[String],
+) -> Result<()> {
+    if from_hook && std::env::var(SKIP_ENV).is_ok_and(|v| v == "1") {
+        eprintln!("gitclaude: {}=1, skipping {}", SKIP_ENV, event);
+        return Ok(());
+    }
+
+    let result = match excluded_reason(from_hook) {
+        Ok(Some(reason)) => {
+            tracing::debug!("Skipping {}: {}", event, reason);
+            if !from_hook {
+                println!("Skipping: {}", reason);
+            }
+            return Ok(());
+        }
+        Ok(None) => dispatch(event, dry_run, profile, from_hook, deferred, blocking, args).await,
+        Err(e) => Err(e),
+    };
+
+    match result {
+        // The verdict is already printed, the exit code is what stops git
+        Err(e) if e.is::<Blocked>() => std::process::exit(1),
+        // A failing review only stops git when a blocking event fails closed
+        Err(e) if from_hook => {
+            eprintln!("gitclaude: {} failed: {:#}", event, e);
+            if blocking && !fails_open(event, profile) {
+                eprintln!("gitclaude: {} does not fail open, set {}=1 to skip it", event, SKIP_ENV);
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+        other => other,
+    }
+}
+
+/// Why hooks should leave this repo alone, if they should. Runs before
+/// stdin is read or the repo config and git2 are touched, so excluded repos
+/// cost almost nothing; only the global config decides this.
+fn excluded_reason(from_hook: bool) -> Result<Option<String>> {
+    let repo_root = config::find_repo_root()?;
+    let global = config::merge_configs(config::load_global_config()?, None, &config::load_overrides()?)?;
+
+    Ok(hooks::excluded_reason(&global.global, &repo_root, from_hook))
+}
+
+/// Read what git handed the hook and run the event, or the tag events
+/// its hook also stands for
+async fn dispatch(
+    event: &str,
+    dry_run: bool,
+    profile: Option<&str>,
+    from_hook: bool,
+    deferred: bool,
+    blocking: bool,
+    args: &[String],
+) -> Result<()> {
+    // The pre-commit framework consumes pre-push's stdin and describes the
+    // push in its environment instead
+    let from_framework = (from_hook && !deferred && event == "pre-push")
+        .then(context::pre_commit_push_input)
+        .flatten();
+
+    // A deferred run gets the hook's stdin passed on, whatever the event
+    let input = match from_framework {
+        Some(input) => input,
+        None => HookInput {
+            args: args.to_vec(),
+            stdin: if from_hook && (deferred || hooks::reads_stdin(event)) {
+                std::io::read_to_string(std::io::stdin())?
+            } else {
+                String::new()
+            },
+        },
+    };
+
+    match event {
+        // Not an event of its own, only how tag creation is noticed
+        "reference-transaction" => run_tag_events(context::created_tags(&input), "create", &input).await,
+        "pre-push" if from_hook && !deferred => {
+            let tags = run_tag_events(context::pushed_tags(&input), "push", &input).await;
+            let push = if installed_for_tags_only(event) {
+                Ok(())
+            } else {
+                run_event(event, dry_run, profile, from_hook, deferred, blocking, &input).await
+            };
+            tags.and(push)
+        }
+        _ => run_event(event, dry_run, profile, from_hook, deferred, blocking, &input).await,
+    }
+}
+
+/// Whether git may carry on after `event` failed: always unless the event
+/// blocks, then only if it fails open. A config that no longer loads can't
+/// say it fails open, so it doesn't.
+fn fails_open(event: &str, profile: Option<&str>) -> bool {
+    config::load_config()
+        .and_then(|config| config.with_profile(event, profile))
+        .is_ok_and(|config| {
+            let event_config = config.event(event);
+            event_config.blocking != Some(true) || event_config.fail_open
+        })
+}
+
+async fn run_event(
+    event: &str,
+    dry_run: bool,
+    profile: Option<&str>,
+    from_hook: bool,
+    deferred: bool,
+    blocking: bool,
+    input: &HookInput,
+) -> Result<()> {
+    let repo_root = config::find_repo_root()?;
+
+    let config = config::load_config()?.with_profile(event, profile)?;
+
+    if !from_hook {
+        println!("🚀 Manuellt triggar event: {}", event);
+    }
+
+    let event_config = config.event(event);
+
+    if !event_config.enabled && !dry_run {
+        println!("Event '{}' is disabled, skipping.", event);
+        return Ok(());
+    }
+
+    // Hooks pass --blocking for events that can stop git; the event config
+    // decides whether this one does
+    let blocking = blocking && event_config.blocking == Some(true);
+
+    if event == "prepare-commit-msg" {
+        if let Some(reason) = message_not_drafted(input) {
+            tracing::debug!("Skipping {}: {}", event, reason);
+            if !from_hook {
+                println!("Skipping: {}", reason);
+            }
+            return Ok(());
+        }
+    }
+
+    if event == "post-checkout" {
+        let repo = git2::Repository::open(&repo_root)?;
+        if let Some(reason) = context::checkout_skip_reason(&repo, input) {
+            tracing::debug!("Skipping {}: {}", event, reason);
+            if !from_hook {
+                println!("Skipping: {}", reason);
+            }
+            return Ok(());
+        }
+    }
+
+    if from_hook && !deferred && !dry_run && !blocking && event_config.defer == Some(true) {
+        return spawn_deferred(event, input);
+    }
+    if deferred && event_config.hook(event) == "pre-push" && !wait_for_push(&repo_root, input).await {
+        tracing::debug!("Push did not complete, skipping {}", event);
+        return Ok(());
+    }
+
+    if event == "post-commit" && from_hook && handles_rewrites(&config) {
+        let repo = git2::Repository::open(&repo_root)?;
+        if context::is_rewriting(&repo) {
+            tracing::debug!("Amend or rebase in progress, leaving it to post-rewrite");
+            return Ok(());
+        }
+    }
+
+    if event == "post-rewrite" && !dry_run {
+        let carried = carry_over_reviews(&repo_root, input, &config)?;
+        if carried > 0 && !from_hook {
+            println!("Carried {} review(s) over to unchanged commits", carried);
+        }
+    }
+
+    let actions = plan_actions(event, &event_config);
+
+    // Actions sharing a context level share the built context
+    let mut contexts: HashMap<String, Context> = HashMap::new();
+    for action in &actions {
+        if !contexts.contains_key(&action.context) {
+            let level = ContextLevel::parse(&action.context);
+            let git_context = context::build_context(&repo_root, event, level, &config, input)?;
+            contexts.insert(action.context.clone(), git_context);
+        }
+    }
+
+    let first = &contexts[&actions[0].context];
+    if let Some(reason) = context::unmet_condition(&event_config.when, first) {
+        tracing::debug!("Skipping {}: {}", event, reason);
+        if !from_hook {
+            println!("Skipping: {}", reason);
+        }
+        return Ok(());
+    }
+    let commit_hash = first.commit_hash.clone();
+
+    if event == "post-rewrite" && first.commits.is_empty() {
+        tracing::debug!("No rewritten commit changed, nothing to review");
+        if !from_hook {
+            println!("No rewritten commit changed, nothing to review");
+        }
+        return Ok(());
+    }
+
+    if event == "post-checkout" && first.commits.is_empty() {
+        tracing::debug!("Branch has no commits of its own, nothing to brief");
+        if !from_hook {
+            println!("Branch has no commits of its own, nothing to brief");
+        }
+        return Ok(());
+    }
+
+    let mut prompts = Vec::with_capacity(actions.len());
+    for action in &actions {
+        let git_context = &contexts[&action.context];
+        prompts.push(templates::render_template(&action.template, git_context, &config.templates)?);
+    }
 
     if dry_run {
         println!();
         println!("─── DRY RUN ───");
-        println!();
-        // TODO: Show what would be sent
-        println!("Template: review");
-        println!("Context level: standard");
-        println!();
-        println!("# Generated prompt:");
-        println!("───────────────────");
-        println!("# Code Review Request");
-        println!("**Commit:** `abc1234`");
-        println!("**Message:** Fix authentication bug");
-        println!("...");
-        println!("───────────────────");
+        if let Some(name) = config.profile_for(event, profile) {
+            println!();
+            println!("Profile: {}", name);
+        }
+        for (action, prompt) in actions.iter().zip(&prompts) {
+            println!();
+            if actions.len() > 1 {
+                println!("Action: {}", action.name);
+            }
+            println!("Template: {}", action.template);
+            println!("Context level: {}", action.context);
+            println!();
+            println!("# Generated prompt:");
+            println!("───────────────────");
+            println!("{}", prompt);
+            println!("───────────────────");
+        }
         println!();
         println!("Kör utan --dry-run för att skicka till Claude.");
-    } else {
-        // TODO: Actually run the event
-        println!("Kör Claude...");
+        return Ok(());
+    }
+
+    let mut pending = vec![];
+    for (action, prompt) in actions.into_iter().zip(prompts) {
+        // A gate that can be debounced lets changes through unchecked
+        if blocking {
+            pending.push((action, prompt));
+            continue;
+        }
+
+        let label = if action.bucket == rate_limit::DEFAULT_BUCKET {
+            String::new()
+        } else {
+            format!("{}: ", action.name)
+        };
+
+        match rate_limit::should_run(&config.rate_limit, &repo_root, &action.bucket)? {
+            RateLimitDecision::Run => pending.push((action, prompt)),
+            RateLimitDecision::Skip { reason } => {
+                println!("{}Skipping: {}", label, reason);
+            }
+            RateLimitDecision::Debounce { seconds } => {
+                println!("{}Debounced, last run was less than {}s ago", label, seconds);
+            }
+            RateLimitDecision::Batch => {
+                println!("{}Added to batch", label);
+            }
+        }
+    }
+
+    if pending.is_empty() {
+        return Ok(());
+    }
+
+    println!("Kör Claude...");
+    let results = run_actions(&pending, &config).await;
+
+    let mut verdict = Verdict::Pass;
+    let mut failed = vec![];
+    for ((action, _), result) in pending.iter().zip(results) {
+        let response = match result {
+            Ok(response) if response.success => Ok(response.content),
+            Ok(_) => Err(anyhow::anyhow!("Claude exited with an error")),
+            Err(e) => Err(e),
+        };
+
+        if blocking {
+            verdict = verdict.worst(match &response {
+                Ok(content) => Verdict::parse(content),
+                Err(e) if event_config.fail_open => Verdict::Warn(format!("not validated, {:#}", e)),
+                Err(e) => Verdict::Fail(format!("not validated, {:#}", e)),
+            });
+        }
+
+        let outcome = response.and_then(|content| {
+            if blocking && action.output.is_empty() {
+                // The verdict printed below stands in for the response
+                return Ok(());
+            }
+            if !blocking {
+                rate_limit::record_run(&repo_root, &action.bucket)?;
+            }
+            handle_response(
+                &content,
+                &action.label(event),
+                &commit_hash,
+                &action.output,
+                &config,
+                &repo_root,
+                input,
+            )
+        });
+
+        if let Err(e) = outcome {
+            if pending.len() > 1 {
+                eprintln!("❌ {}: {:#}", action.name, e);
+            }
+            failed.push((action.name.clone(), e));
+        }
+    }
+
+    if blocking {
+        println!("{}", verdict);
+        if verdict.exit_code() != 0 {
+            eprintln!("gitclaude: {} blocked, set {}=1 to skip the check", event, SKIP_ENV);
+            return Err(Blocked(verdict).into());
+        }
+    }
+
+    match failed.len() {
+        0 => Ok(()),
+        1 if pending.len() == 1 => Err(failed.remove(0).1),
+        n => anyhow::bail!("{} of {} actions failed", n, pending.len()),
+    }
+}
+
+/// Why git already has a message for this commit, going by the source
+/// argument of prepare-commit-msg: `-m`/`-F`, a merge or squash, or the
+/// message of an existing commit (`--amend`, `-c`, `-C`)
+fn message_not_drafted(input: &HookInput) -> Option<&'static str> {
+    match input.args.get(1).map(String::as_str) {
+        Some("message") => Some("message given on the command line"),
+        Some("merge") => Some("merge commit"),
+        Some("squash") => Some("squash commit"),
+        Some("commit") => Some("message reused from an existing commit"),
+        _ => None,
+    }
+}
+
+/// Whether post-rewrite is enabled and so reviews amends and rebases
+fn handles_rewrites(config: &Config) -> bool {
+    config.events.get("post-rewrite").is_some_and(|e| e.enabled)
+}
+
+/// Give rewritten commits whose changes are unchanged the notes and saved
+/// responses of the commits they replace. Returns how many were carried.
+fn carry_over_reviews(repo_root: &Path, input: &HookInput, config: &Config) -> Result<usize> {
+    let repo = git2::Repository::open(repo_root)?;
+    let mut carried = 0;
+
+    for rewrite in context::parse_rewrites(&repo, input)?.iter().filter(|r| r.unchanged) {
+        let old = &rewrite.old.to_string()[..7];
+        let new = &rewrite.new.to_string()[..7];
+
+        let note = output::copy_git_note(&repo, rewrite.old, rewrite.new, &config.output.git_note)?;
+        let files = output::copy_responses(repo_root, &config.output.file, old, new)?;
+
+        if note || files > 0 {
+            tracing::debug!("Carried review of {} over to {}", old, new);
+            carried += 1;
+        }
+    }
+
+    Ok(carried)
+}
+
+/// Run the tag event for each of `tags`, if `trigger` is what the tag
+/// event is configured to run on
+async fn run_tag_events(tags: Vec<String>, trigger: &str, input: &HookInput) -> Result<()> {
+    if tags.is_empty() {
+        return Ok(());
+    }
+    let tag_config = config::load_config()?.event("tag");
+    if !tag_config.enabled || tag_config.trigger.as_deref() != Some(trigger) {
+        return Ok(());
+    }
+
+    for tag in tags {
+        let input = HookInput {
+            args: vec![tag],
+            stdin: input.stdin.clone(),
+        };
+        run_event("tag", false, None, true, false, false, &input).await?;
+    }
+    Ok(())
+}
+
+/// Whether the hook for `event` is only there for the tag event, so the
+/// event itself has no config and should not run on its defaults
+fn installed_for_tags_only(event: &str) -> bool {
+    config::load_config().is_ok_and(|config| {
+        !config.events.contains_key(event) && config.events.get("tag").is_some_and(|tag| tag.hook("tag") == event)
+    })
+}
+
+/// Hand the work to a detached copy of gitclaude so git can carry on
+fn spawn_deferred(event: &str, input: &HookInput) -> Result<()> {
+    let mut child = std::process::Command::new(std::env::current_exe()?)
+        .args(["run", event, "--from-hook", "--deferred", "--"])
+        .args(&input.args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .context("Failed to start deferred gitclaude")?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input.stdin.as_bytes())?;
+    }
+
+    tracing::debug!("Deferred {} to process {}", event, child.id());
+    Ok(())
+}
+
+/// Wait until the pushed refs show up on the remote-tracking refs.
+///
+/// Returns false if they don't within [`PUSH_WAIT`], e.g. because the push
+/// was rejected. Pushes that can't be checked get a short grace period.
+async fn wait_for_push(repo_root: &Path, input: &HookInput) -> bool {
+    let started = std::time::Instant::now();
+
+    while started.elapsed() < PUSH_WAIT {
+        let landed = git2::Repository::open(repo_root)
+            .ok()
+            .and_then(|repo| context::push_landed(&repo, input));
+
+        match landed {
+            Some(true) => return true,
+            Some(false) => tokio::time::sleep(Duration::from_secs(1)).await,
+            None => {
+                tokio::time::sleep(Duration::from_secs(2)).await;
+                return true;
+            }
+        }
+    }
+
+    false
+}
+
+/// A single Claude run planned for an event
+struct Action {
+    name: String,
+    template: String,
+    context: String,
+    output: Vec<String>,
+    bucket: String,
+}
+
+impl Action {
+    /// Name used for notifications and response files
+    fn label(&self, event: &str) -> String {
+        if self.bucket == rate_limit::DEFAULT_BUCKET {
+            event.to_string()
+        } else {
+            format!("{}-{}", event, self.name)
+        }
+    }
+}
+
+/// Expand an event into its actions, falling back to a single action made
+/// from the event's own template
+fn plan_actions(event: &str, event_config: &EventConfig) -> Vec<Action> {
+    if event_config.actions.is_empty() {
+        return vec![Action {
+            name: event.to_string(),
+            template: event_config.template.clone(),
+            context: event_config.context.clone(),
+            output: event_config.output.clone(),
+            bucket: rate_limit::DEFAULT_BUCKET.to_string(),
+        }];
+    }
+
+    event_config
+        .actions
+        .iter()
+        .map(|a| {
+            let name = if a.name.is_empty() { a.template.clone() } else { a.name.clone() };
+            Action {
+                template: a.template.clone(),
+                context: a.context.clone().unwrap_or_else(|| event_config.context.clone()),
+                output: a.output.clone().unwrap_or_else(|| event_config.output.clone()),
+                bucket: a.bucket.clone().unwrap_or_else(|| name.clone()),
+                name,
+            }
+        })
+        .collect()
+}
+
+/// Run Claude for each action, at most `general.max_parallel_actions` at a
+/// time. Results come back in the order of `pending`.
+async fn run_actions(pending: &[(Action, String)], config: &Config) -> Vec<Result<ClaudeResponse>> {
+    let limit = Arc::new(Semaphore::new(config.general.max_parallel_actions.max(1)));
+    let mut tasks = JoinSet::new();
+
+    for (index, (_, prompt)) in pending.iter().enumerate() {
+        let limit = limit.clone();
+        let prompt = prompt.clone();
+        let claude_config = config.claude.clone();
+
+        tasks.spawn(async move {
+            let _permit = limit.acquire_owned().await;
+            (index, claude::run_claude(&prompt, &claude_config).await)
+        });
+    }
+
+    let mut results: Vec<Option<Result<ClaudeResponse>>> = pending.iter().map(|_| None).collect();
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok((index, result)) => results[index] = Some(result),
+            Err(e) => tracing::error!("Action task failed: {}", e),
+        }
+    }
+
+    results
+        .into_iter()
+        .map(|r| r.unwrap_or_else(|| Err(anyhow::anyhow!("Action did not complete"))))
+        .collect()
+}
+
+/// Send the response to every output configured for the action
+fn handle_response(
+    response: &str,
+    event: &str,
+    commit_hash: &str,
+    outputs: &[String],
+    config: &Config,
+    repo_root: &Path,
+    input: &HookInput,
+) -> Result<()> {
+    if config.general.log_responses && !outputs.iter().any(|o| o == "file") {
+        output::save_response(response, event, commit_hash, repo_root, &config.output.file)?;
+    }
+
+    if outputs.is_empty() {
         println!();
-        println!("✅ Response:");
-        println!("Ser bra ut! Inga uppenbara problem.");
+        println!("✅ Response ({}):", event);
+        println!("{}", response);
+        return Ok(());
+    }
+
+    for target in outputs {
+        match target.as_str() {
+            "notify" if config.general.notify => {
+                output::notify_response(response, event, &config.output.notify)?;
+            }
+            "notify" => {}
+            "file" => {
+                let path = output::save_response(response, event, commit_hash, repo_root, &config.output.file)?;
+                println!("Response saved to {}", path.display());
+            }
+            "terminal" => {
+                output::open_in_terminal(response, &config.output.terminal)?;
+            }
+            "git_note" => {
+                output::add_git_note(response, commit_hash, repo_root, &config.output.git_note)?;
+            }
+            "commit_msg" => match input.args.first() {
+                Some(message_file) => {
+                    let message_file = repo_root.join(message_file);
+                    output::write_commit_msg(response, &message_file, repo_root, &config.output.commit_msg)?;
+                }
+                None => {
+                    println!();
+                    println!("✅ Suggested commit message:");
+                    println!("{}", response.trim());
+                }
+            },
+            "changelog" => match input.args.first() {
+                Some(tag) if event == "tag" => {
+                    let path = output::write_changelog_draft(response, tag, repo_root, &config.output.changelog)?;
+                    println!("Release notes draft written to {}", path.display());
+                }
+                _ => tracing::warn!("The changelog output only applies to the tag event"),
+            },
+            other => {
+                tracing::warn!("Unsupported output '{}'", other);
+            }
+        }
     }
 
     Ok(())
@@ -1,26 +1,89 @@
 use anyhow::Result;
 
+use crate::config;
+use crate::hooks;
+
 pub async fn run(verbose: bool) -> Result<()> {
+    let config = config::load_config()?.resolved()?;
+    let repo_root = config::find_repo_root().ok();
+
     println!("📊 Gitclaude Status");
     println!("─────────────────────");
 
-    // TODO: Implement actual status checks
-    println!("Global listening: ❌ Disabled");
-    println!("Current repo:     ✅ Enabled");
-    println!("Active events:    post-commit");
-    println!("Rate limiting:    debounce (30s)");
-    println!("Context level:    standard");
-    println!("Output:           notify");
+    println!("Global listening: {}", on_off(config.global.listen_globally));
+
+    let Some(repo_root) = repo_root else {
+        println!("Current repo:     not in a git repository");
+        return Ok(());
+    };
+
+    let enabled = hooks::is_enabled(&repo_root);
+    println!("Current repo:     {}", on_off(enabled));
+
+    let mode = hooks::integration_mode(&repo_root)?;
+    match &mode {
+        hooks::IntegrationMode::Manager(manager) => println!(
+            "Integration:      {} ({})",
+            mode,
+            manager.config_path(&repo_root).display()
+        ),
+        _ => println!("Integration:      {}", mode),
+    }
+
+    let mut events: Vec<&String> = config
+        .events
+        .iter()
+        .filter(|(_, e)| e.enabled)
+        .map(|(name, _)| name)
+        .collect();
+    events.sort();
+    let events: Vec<&str> = events.iter().map(|e| e.as_str()).collect();
+    println!("Active events:    {}", if events.is_empty() { "none".to_string() } else { events.join(", ") });
+
+    let rate_limit = &config.rate_limit;
+    let setting = match rate_limit.strategy.as_str() {
+        "debounce" => format!(" ({}s)", rate_limit.debounce_seconds),
+        "cooldown" => format!(" ({}min)", rate_limit.cooldown_minutes),
+        "batch" => format!(" ({}s window)", rate_limit.batch_window_seconds),
+        "smart" => format!(" ({}s, max {}/h)", rate_limit.debounce_seconds, rate_limit.max_runs_per_hour),
+        _ => String::new(),
+    };
+    println!("Rate limiting:    {}{}", rate_limit.strategy, setting);
 
     if verbose {
         println!();
         println!("📁 Config Paths");
-        println!("   Global: ~/.config/gitclaude/config.toml");
-        println!("   Repo:   .gitclaude/config.toml");
+        if let Ok(path) = config::global_config_path() {
+            println!("   Global: {}", path.display());
+        }
+        println!("   Repo:   {}", repo_root.join(".gitclaude").join("config.toml").display());
+
         println!();
         println!("🪝 Installed Hooks");
-        println!("   .git/hooks/post-commit → gitclaude");
+        let hooks_dir = hooks::hooks_dir(&repo_root)?;
+        let installed = hooks::installed_hooks(&repo_root)?;
+        if installed.is_empty() {
+            println!("   none in {}", hooks_dir.display());
+        }
+        for event in installed {
+            println!("   {} → gitclaude", hooks_dir.join(event).display());
+        }
+
+        println!();
+        println!("⚙️  Events");
+        for name in &events {
+            let event = config.event(name);
+            println!("   {}: template {}, context {}", name, event.template, event.context);
+        }
     }
 
     Ok(())
 }
+
+fn on_off(enabled: bool) -> &'static str {
+    if enabled {
+        "✅ Enabled"
+    } else {
+        "❌ Disabled"
+    }
+}
@@ -0,0 +1,89 @@
+use anyhow::{Context, Result};
+use toml_edit::{DocumentMut, Item, TableLike};
+
+/// Look up a dotted key in a TOML value
+pub fn get_value<'a>(value: &'a toml::Value, key: &str) -> Option<&'a toml::Value> {
+    key.split('.').try_fold(value, |current, part| current.get(part))
+}
+
+/// Parse a command-line value as TOML, falling back to a plain string
+pub fn parse_value(raw: &str) -> toml_edit::Value {
+    raw.parse::<toml_edit::Value>()
+        .unwrap_or_else(|_| toml_edit::Value::from(raw))
+}
+
+/// Set a dotted key in a config file's contents, preserving comments and order
+pub fn set_value(content: &str, key: &str, value: toml_edit::Value) -> Result<String> {
+    let mut doc: DocumentMut = content.parse().context("Failed to parse config")?;
+    let (parents, leaf) = split_key(key)?;
+
+    let mut table: &mut dyn TableLike = doc.as_table_mut();
+    for part in parents {
+        let item = table.entry(part).or_insert_with(|| {
+            let mut new_table = toml_edit::Table::new();
+            new_table.set_implicit(true);
+            Item::Table(new_table)
+        });
+
+        table = item
+            .as_table_like_mut()
+            .with_context(|| format!("`{}` is not a table", part))?;
+    }
+
+    match table.get_mut(leaf) {
+        Some(Item::Value(existing)) => {
+            // Keep inline comments and spacing around the old value
+            let decor = existing.decor().clone();
+            *existing = value;
+            *existing.decor_mut() = decor;
+        }
+        _ => {
+            table.insert(leaf, Item::Value(value));
+        }
+    }
+
+    Ok(doc.to_string())
+}
+
+/// Remove a dotted key from a config file's contents.
+///
+/// Tables left empty by the removal are dropped as well.
+pub fn unset_value(content: &str, key: &str) -> Result<String> {
+    let mut doc: DocumentMut = content.parse().context("Failed to parse config")?;
+    let parts: Vec<&str> = key.split('.').collect();
+
+    if !remove_path(doc.as_table_mut(), &parts) {
+        anyhow::bail!("Key `{}` is not set", key);
+    }
+
+    Ok(doc.to_string())
+}
+
+fn remove_path(table: &mut dyn TableLike, parts: &[&str]) -> bool {
+    match parts {
+        [] => false,
+        [leaf] => table.remove(leaf).is_some(),
+        [head, rest @ ..] => {
+            let Some(child) = table.get_mut(head).and_then(Item::as_table_like_mut) else {
+                return false;
+            };
+
+            let removed = remove_path(child, rest);
+            if removed && child.is_empty() {
+                table.remove(head);
+            }
+            removed
+        }
+    }
+}
+
+fn split_key(key: &str) -> Result<(Vec<&str>, &str)> {
+    let mut parts: Vec<&str> = key.split('.').collect();
+
+    if parts.iter().any(|p| p.is_empty()) {
+        anyhow::bail!("Invalid key `{}`", key);
+    }
+
+    let leaf = parts.pop().context("Empty key")?;
+    Ok((parts, leaf))
+}
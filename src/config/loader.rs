@@ -1,64 +1,158 @@
 use anyhow::{Context, Result};
 use std::path::{Path, PathBuf};
 
+use toml::Table;
+
+use super::merge::{merge_policy, merge_tables};
+use super::migrate::migrate;
+use super::overrides::{apply_overrides, load_overrides, Override};
+use super::policy::capture_locks;
 use super::types::Config;
 
 /// Load configuration with proper precedence:
 /// 1. Repo-specific (.gitclaude/config.toml)
 /// 2. Global (~/.config/gitclaude/config.toml)
+///
+/// Layers are merged key by key, so a repo file only needs the keys it
+/// overrides. Each layer's `include` files are merged beneath it.
+/// `GITCLAUDE_*` environment variables and `-c key=value` flags are applied
+/// on top, except for keys locked by the repo's `[policy]`.
 pub fn load_config() -> Result<Config> {
     let global_config = load_global_config()?;
     let repo_config = load_repo_config()?;
 
-    Ok(merge_configs(global_config, repo_config))
+    merge_configs(global_config, repo_config, &load_overrides()?)
 }
 
-/// Load global configuration
-pub fn load_global_config() -> Result<Option<Config>> {
+/// Load global configuration as a raw TOML table
+pub fn load_global_config() -> Result<Option<Table>> {
     let path = global_config_path()?;
 
     if !path.exists() {
         return Ok(None);
     }
 
-    let content = std::fs::read_to_string(&path)
-        .context("Failed to read global config")?;
-
-    let config: Config = toml::from_str(&content)
-        .context("Failed to parse global config")?;
-
-    Ok(Some(config))
+    read_layer(&path)
+        .context("Failed to parse global config")
+        .map(Some)
 }
 
-/// Load repository-specific configuration
-pub fn load_repo_config() -> Result<Option<Config>> {
-    let path = repo_config_path()?;
+/// Load repository-specific configuration as a raw TOML table
+pub fn load_repo_config() -> Result<Option<Table>> {
+    let Ok(path) = repo_config_path() else {
+        return Ok(None);
+    };
 
     if !path.exists() {
         return Ok(None);
     }
 
-    let content = std::fs::read_to_string(&path)
-        .context("Failed to read repo config")?;
+    read_layer(&path)
+        .context("Failed to parse repo config")
+        .map(Some)
+}
+
+/// Read a config file, migrating older layouts in memory and merging its
+/// includes beneath it
+fn read_layer(path: &Path) -> Result<Table> {
+    read_layer_with_includes(path, &mut vec![])
+}
+
+fn read_layer_with_includes(path: &Path, stack: &mut Vec<PathBuf>) -> Result<Table> {
+    let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    if stack.contains(&canonical) {
+        let chain: Vec<String> = stack
+            .iter()
+            .chain(std::iter::once(&canonical))
+            .map(|p| p.display().to_string())
+            .collect();
+        anyhow::bail!("Config include cycle: {}", chain.join(" -> "));
+    }
+
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+
+    let migrated = migrate(&content)?;
+    if !migrated.applied.is_empty() {
+        tracing::debug!(
+            "{} uses config version {}, run 'gitclaude config migrate' to update it",
+            path.display(),
+            migrated.from
+        );
+    }
+
+    let mut table: Table = toml::from_str(&migrated.content)
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+
+    let includes: Vec<String> = match table.remove("include") {
+        Some(value) => value
+            .try_into()
+            .with_context(|| format!("`include` in {} must be a list of paths", path.display()))?,
+        None => return Ok(table),
+    };
+
+    stack.push(canonical);
+    let mut layers = vec![];
+    for include in &includes {
+        let include_path = resolve_include(path, include);
+        let layer = read_layer_with_includes(&include_path, stack)
+            .with_context(|| format!("Failed to load include '{}' from {}", include, path.display()))?;
+        layers.push(layer);
+    }
+    stack.pop();
+
+    layers.push(table);
+
+    let policy = merge_policy(&layers.iter().collect::<Vec<_>>())?;
+    let mut merged = Table::new();
+    for layer in layers {
+        merge_tables(&mut merged, layer, &policy, "");
+    }
+
+    Ok(merged)
+}
 
-    let config: Config = toml::from_str(&content)
-        .context("Failed to parse repo config")?;
+/// Resolve an `include` entry relative to the file that includes it
+pub fn resolve_include(including: &Path, include: &str) -> PathBuf {
+    let path = expand_home(include);
+    if path.is_absolute() {
+        return path;
+    }
 
-    Ok(Some(config))
+    including
+        .parent()
+        .map(|dir| dir.join(&path))
+        .unwrap_or(path)
 }
 
-/// Merge configs with repo taking precedence
-fn merge_configs(global: Option<Config>, repo: Option<Config>) -> Config {
-    match (global, repo) {
-        (None, None) => Config::default(),
-        (Some(g), None) => g,
-        (None, Some(r)) => r,
-        (Some(_g), Some(r)) => {
-            // TODO: Implement proper merging where repo overrides global
-            // For now, just use repo config if it exists
-            r
+/// Expand a leading `~/` to the home directory
+pub fn expand_home(path: &str) -> PathBuf {
+    if let Some(rest) = path.strip_prefix("~/") {
+        if let Some(home) = dirs::home_dir() {
+            return home.join(rest);
         }
     }
+    PathBuf::from(path)
+}
+
+/// Merge configs with repo taking precedence, then apply overrides
+pub fn merge_configs(global: Option<Table>, repo: Option<Table>, overrides: &[Override]) -> Result<Config> {
+    let locks = capture_locks(repo.as_ref())?;
+
+    let layers: Vec<Table> = global.into_iter().chain(repo).collect();
+    let policy = merge_policy(&layers.iter().collect::<Vec<_>>())?;
+
+    let mut merged = Table::new();
+    for layer in layers {
+        merge_tables(&mut merged, layer, &policy, "");
+    }
+
+    let mut config: Config = toml::Value::Table(merged)
+        .try_into()
+        .context("Failed to parse merged config")?;
+    config.locks = locks;
+
+    apply_overrides(&config, overrides)
 }
 
 /// Get path to global config file
@@ -91,6 +185,21 @@ pub fn find_repo_root() -> Result<PathBuf> {
     }
 }
 
+/// Git directory shared by all worktrees of a repo: `.git` in a normal
+/// checkout, `.git/modules/<name>` in a submodule
+pub fn git_common_dir(repo_root: &Path) -> Result<PathBuf> {
+    let repo = git2::Repository::open(repo_root)
+        .with_context(|| format!("Failed to open repository at {}", repo_root.display()))?;
+    let common = repo.commondir();
+
+    Ok(common.canonicalize().unwrap_or_else(|_| common.to_path_buf()))
+}
+
+/// Where gitclaude keeps runtime state for a repo, shared by its worktrees
+pub fn state_dir(repo_root: &Path) -> Result<PathBuf> {
+    Ok(git_common_dir(repo_root)?.join("gitclaude"))
+}
+
 /// Save configuration to file
 pub fn save_config(config: &Config, path: &Path) -> Result<()> {
     let content = toml::to_string_pretty(config)
@@ -0,0 +1,55 @@
+use anyhow::{Context, Result};
+use toml::{Table, Value};
+
+use super::types::MergeConfig;
+
+/// Deep-merge `overlay` on top of `base`.
+///
+/// Tables are merged key by key, scalars from `overlay` win, and arrays are
+/// either replaced or appended according to the merge policy. `path` is the
+/// dotted key of `base` and is used to look up per-key list policies.
+pub fn merge_tables(base: &mut Table, overlay: Table, policy: &MergeConfig, path: &str) {
+    for (key, value) in overlay {
+        let key_path = if path.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", path, key)
+        };
+
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(base_table)), Value::Table(overlay_table)) => {
+                merge_tables(base_table, overlay_table, policy, &key_path);
+            }
+            (Some(Value::Array(base_array)), Value::Array(overlay_array))
+                if policy.appends(&key_path) =>
+            {
+                for item in overlay_array {
+                    if !base_array.contains(&item) {
+                        base_array.push(item);
+                    }
+                }
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Resolve the merge policy for a stack of layers.
+///
+/// The `[merge]` section is itself merged shallowly, with the topmost layer
+/// that sets a key winning.
+pub fn merge_policy(layers: &[&Table]) -> Result<MergeConfig> {
+    let mut section = Table::new();
+
+    for layer in layers {
+        if let Some(Value::Table(merge)) = layer.get("merge") {
+            for (key, value) in merge {
+                section.insert(key.clone(), value.clone());
+            }
+        }
+    }
+
+    Value::Table(section).try_into().context("Invalid [merge] section")
+}
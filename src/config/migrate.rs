@@ -0,0 +1,136 @@
+use anyhow::{Context, Result};
+use toml_edit::{DocumentMut, Item, Key, Table};
+
+/// Current config file layout version
+pub const CONFIG_VERSION: u32 = 3;
+
+/// A rewrite from one config layout version to the next
+struct Migration {
+    from: u32,
+    description: &'static str,
+    apply: fn(&mut DocumentMut),
+}
+
+/// All migrations, in order
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        from: 1,
+        description: "rename general.async to async_mode and output.git_note.ref to note_ref",
+        apply: v1_to_v2,
+    },
+    Migration {
+        from: 2,
+        description: "rename the post-push event to pre-push, the hook git actually runs",
+        apply: v2_to_v3,
+    },
+];
+
+/// Result of migrating a config file
+#[derive(Debug)]
+pub struct Migrated {
+    pub from: u32,
+    pub content: String,
+    pub applied: Vec<&'static str>,
+}
+
+/// Version declared by a config file; files without one predate versioning
+pub fn file_version(doc: &DocumentMut) -> u32 {
+    doc.get("version")
+        .and_then(Item::as_integer)
+        .map(|v| v as u32)
+        .unwrap_or(1)
+}
+
+/// Rewrite a config file to the current layout, preserving comments
+pub fn migrate(content: &str) -> Result<Migrated> {
+    let mut doc: DocumentMut = content.parse().context("Failed to parse config")?;
+    let from = file_version(&doc);
+
+    if from > CONFIG_VERSION {
+        anyhow::bail!(
+            "Config version {} is newer than this gitclaude supports ({}), please upgrade",
+            from,
+            CONFIG_VERSION
+        );
+    }
+
+    let mut applied = vec![];
+    for migration in MIGRATIONS.iter().filter(|m| m.from >= from) {
+        (migration.apply)(&mut doc);
+        applied.push(migration.description);
+    }
+
+    if applied.is_empty() {
+        return Ok(Migrated {
+            from,
+            content: content.to_string(),
+            applied,
+        });
+    }
+
+    doc.insert("version", toml_edit::value(CONFIG_VERSION as i64));
+
+    Ok(Migrated {
+        from,
+        content: doc.to_string(),
+        applied,
+    })
+}
+
+fn v1_to_v2(doc: &mut DocumentMut) {
+    if let Some(general) = doc.get_mut("general").and_then(Item::as_table_mut) {
+        rename_key(general, "async", "async_mode");
+    }
+
+    let git_note = doc
+        .get_mut("output")
+        .and_then(Item::as_table_mut)
+        .and_then(|output| output.get_mut("git_note"))
+        .and_then(Item::as_table_mut);
+
+    if let Some(git_note) = git_note {
+        rename_key(git_note, "ref", "note_ref");
+    }
+}
+
+fn v2_to_v3(doc: &mut DocumentMut) {
+    if let Some(events) = doc.get_mut("events").and_then(Item::as_table_mut) {
+        rename_key(events, "post-push", "pre-push");
+    }
+
+    let Some(profiles) = doc.get_mut("profiles").and_then(Item::as_table_mut) else {
+        return;
+    };
+
+    for (_, profile) in profiles.iter_mut() {
+        let templates = profile
+            .as_table_mut()
+            .and_then(|p| p.get_mut("templates"))
+            .and_then(Item::as_table_mut);
+
+        if let Some(templates) = templates {
+            rename_key(templates, "post-push", "pre-push");
+        }
+    }
+}
+
+/// Rename a key in place, keeping its position and formatting
+fn rename_key(table: &mut Table, from: &str, to: &str) {
+    if !table.contains_key(from) || table.contains_key(to) {
+        return;
+    }
+
+    let keys: Vec<String> = table.iter().map(|(k, _)| k.to_string()).collect();
+    let entries: Vec<(Key, Item)> = keys.iter().filter_map(|k| table.remove_entry(k)).collect();
+
+    for (key, item) in entries {
+        let key = if key.get() == from {
+            Key::new(to)
+                .with_leaf_decor(key.leaf_decor().clone())
+                .with_dotted_decor(key.dotted_decor().clone())
+        } else {
+            key
+        };
+        table.insert_formatted(&key, item);
+    }
+}
@@ -1,7 +1,22 @@
 //! Configuration loading and management
 
+mod edit;
 mod loader;
+mod merge;
+mod migrate;
+mod origin;
+mod overrides;
+mod policy;
+mod profile;
+mod schema;
 mod types;
+mod validate;
 
+pub use edit::*;
 pub use loader::*;
+pub use migrate::*;
+pub use origin::*;
+pub use overrides::*;
+pub use schema::config_schema;
 pub use types::*;
+pub use validate::*;
@@ -0,0 +1,146 @@
+use anyhow::Result;
+use toml::{Table, Value};
+
+use super::merge::merge_policy;
+use super::overrides::OverrideSource;
+use super::types::Config;
+
+/// Keys that may be spelled differently in a config file
+const ALIASES: &[(&str, &str)] = &[
+    ("general.async_mode", "general.async"),
+    ("output.git_note.note_ref", "output.git_note.ref"),
+];
+
+/// Where an effective config value came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Origin {
+    Default,
+    Global,
+    Profile(String),
+    Repo,
+    Env(String),
+    CommandLine,
+}
+
+impl std::fmt::Display for Origin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Origin::Default => write!(f, "default"),
+            Origin::Global => write!(f, "global"),
+            Origin::Profile(name) => write!(f, "profile {}", name),
+            Origin::Repo => write!(f, "repo"),
+            Origin::Env(name) => write!(f, "env {}", name),
+            Origin::CommandLine => write!(f, "-c"),
+        }
+    }
+}
+
+/// An effective config value together with the layers that produced it
+#[derive(Debug, Clone)]
+pub struct TracedValue {
+    pub key: String,
+    pub value: Value,
+    pub origins: Vec<Origin>,
+}
+
+/// Trace every leaf value of the resolved config back to its layer.
+///
+/// `config` is the merged config before profiles are applied; `global` and
+/// `repo` are the raw layers it was merged from. Overrides are taken from
+/// `config.overrides`.
+pub fn trace_origins(
+    config: &Config,
+    global: Option<&Table>,
+    repo: Option<&Table>,
+) -> Result<Vec<TracedValue>> {
+    let base = flatten(&Value::try_from(config)?);
+    let resolved = flatten(&Value::try_from(config.resolved()?)?);
+    let layers: Vec<&Table> = global.into_iter().chain(repo).collect();
+    let policy = merge_policy(&layers)?;
+
+    let traced = resolved
+        .into_iter()
+        .map(|(key, value)| {
+            let in_global = global.is_some_and(|t| contains(t, &key));
+            let in_repo = repo.is_some_and(|t| contains(t, &key));
+
+            let overridden = config.overrides.iter().rev().find(|o| o.key == key);
+
+            let origins = if let Some(o) = overridden {
+                vec![match &o.source {
+                    OverrideSource::Env(name) => Origin::Env(name.clone()),
+                    OverrideSource::CommandLine => Origin::CommandLine,
+                }]
+            } else if !base.iter().any(|(k, v)| k == &key && v == &value) {
+                vec![Origin::Profile(profile_for_key(config, &key))]
+            } else if in_repo && in_global && value.is_array() && policy.appends(&key) {
+                vec![Origin::Global, Origin::Repo]
+            } else if in_repo {
+                vec![Origin::Repo]
+            } else if in_global {
+                vec![Origin::Global]
+            } else {
+                vec![Origin::Default]
+            };
+
+            TracedValue { key, value, origins }
+        })
+        .collect();
+
+    Ok(traced)
+}
+
+/// Name of the profile responsible for a key changed by profile resolution
+fn profile_for_key(config: &Config, key: &str) -> String {
+    let event = key
+        .strip_prefix("events.")
+        .and_then(|rest| config.events.keys().find(|e| rest.starts_with(&format!("{}.", e))));
+
+    event
+        .and_then(|e| config.profile_for(e, None))
+        .or_else(|| config.repo_profile())
+        .unwrap_or_default()
+}
+
+/// Flatten a value into dotted keys and leaf values; arrays are leaves
+fn flatten(value: &Value) -> Vec<(String, Value)> {
+    fn walk(prefix: &str, value: &Value, out: &mut Vec<(String, Value)>) {
+        match value {
+            Value::Table(table) => {
+                for (key, value) in table {
+                    let path = if prefix.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{}.{}", prefix, key)
+                    };
+                    walk(&path, value, out);
+                }
+            }
+            _ => out.push((prefix.to_string(), value.clone())),
+        }
+    }
+
+    let mut out = vec![];
+    walk("", value, &mut out);
+    out
+}
+
+/// Whether a raw layer sets the dotted `key` (or one of its aliases)
+fn contains(table: &Table, key: &str) -> bool {
+    let alias = ALIASES.iter().find(|(k, _)| *k == key).map(|(_, a)| *a);
+
+    std::iter::once(key).chain(alias).any(|key| {
+        let mut current = table;
+        let mut parts = key.split('.').peekable();
+
+        while let Some(part) = parts.next() {
+            match (current.get(part), parts.peek()) {
+                (Some(_), None) => return true,
+                (Some(Value::Table(next)), Some(_)) => current = next,
+                _ => return false,
+            }
+        }
+
+        false
+    })
+}
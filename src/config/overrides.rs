@@ -0,0 +1,166 @@
+use anyhow::{Context, Result};
+use std::sync::OnceLock;
+use toml::{Table, Value};
+
+use super::policy::is_locked;
+use super::types::Config;
+
+/// Prefix for environment overrides, e.g. `GITCLAUDE_CLAUDE__TIMEOUT=300`
+const ENV_PREFIX: &str = "GITCLAUDE_";
+
+static CLI_OVERRIDES: OnceLock<Vec<String>> = OnceLock::new();
+
+/// Where an override came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OverrideSource {
+    /// Environment variable name
+    Env(String),
+    /// `-c key=value` on the command line
+    CommandLine,
+}
+
+/// A single config key set from outside the config files
+#[derive(Debug, Clone)]
+pub struct Override {
+    pub key: String,
+    pub value: Value,
+    pub source: OverrideSource,
+}
+
+/// Register the `-c key=value` flags given on the command line
+pub fn set_cli_overrides(overrides: Vec<String>) {
+    let _ = CLI_OVERRIDES.set(overrides);
+}
+
+/// Collect overrides from the environment and then the command line, so
+/// that `-c` wins over `GITCLAUDE_*` variables
+pub fn load_overrides() -> Result<Vec<Override>> {
+    let mut overrides: Vec<Override> = std::env::vars()
+        .filter_map(|(name, value)| {
+            let key = env_key(&name)?;
+            Some(Override {
+                key,
+                value: parse_toml_value(&value),
+                source: OverrideSource::Env(name),
+            })
+        })
+        .collect();
+
+    overrides.sort_by(|a, b| a.key.cmp(&b.key));
+
+    for raw in CLI_OVERRIDES.get().into_iter().flatten() {
+        let (key, value) = raw
+            .split_once('=')
+            .with_context(|| format!("Invalid override '{}', expected key=value", raw))?;
+
+        overrides.push(Override {
+            key: key.trim().to_string(),
+            value: parse_toml_value(value.trim()),
+            source: OverrideSource::CommandLine,
+        });
+    }
+
+    Ok(overrides)
+}
+
+/// Apply overrides on top of a config, then restore keys locked by the repo
+/// policy
+pub fn apply_overrides(config: &Config, overrides: &[Override]) -> Result<Config> {
+    if overrides.is_empty() && config.locks.is_empty() {
+        return Ok(config.clone());
+    }
+
+    let mut value = Value::try_from(config)?;
+    let root = value.as_table_mut().context("Config is not a table")?;
+    let mut applied = vec![];
+
+    for o in overrides {
+        if is_locked(&config.locks, &o.key) {
+            tracing::warn!("`{}` is locked by the repo policy, ignoring override", o.key);
+            continue;
+        }
+
+        set_path(root, &o.key, o.value.clone())
+            .with_context(|| format!("Invalid override for `{}`", o.key))?;
+        applied.push(o.clone());
+    }
+
+    for lock in &config.locks {
+        match &lock.value {
+            Some(v) => set_path(root, &lock.key, v.clone())?,
+            None => remove_path(root, &lock.key),
+        }
+    }
+
+    let mut updated: Config = value.try_into().with_context(|| {
+        let keys: Vec<&str> = applied.iter().map(|o| o.key.as_str()).collect();
+        format!("Invalid value in overrides ({})", keys.join(", "))
+    })?;
+    updated.overrides = applied;
+    updated.locks = config.locks.clone();
+
+    Ok(updated)
+}
+
+/// Map `GITCLAUDE_SECTION__KEY` to `section.key`.
+///
+/// Levels are separated by a double underscore. Event names use dashes, so
+/// `GITCLAUDE_EVENTS__POST_COMMIT__ENABLED` maps to `events.post-commit.enabled`.
+fn env_key(name: &str) -> Option<String> {
+    let rest = name.strip_prefix(ENV_PREFIX)?;
+    if !rest.contains("__") {
+        return None;
+    }
+
+    let mut parts: Vec<String> = rest.split("__").map(|p| p.to_lowercase()).collect();
+    if parts.iter().any(|p| p.is_empty()) {
+        return None;
+    }
+
+    if parts[0] == "events" && parts.len() > 1 {
+        parts[1] = parts[1].replace('_', "-");
+    }
+
+    Some(parts.join("."))
+}
+
+/// Parse a raw value as TOML, falling back to a plain string
+fn parse_toml_value(raw: &str) -> Value {
+    format!("v = {}", raw)
+        .parse::<Table>()
+        .ok()
+        .and_then(|mut t| t.remove("v"))
+        .unwrap_or_else(|| Value::String(raw.to_string()))
+}
+
+fn set_path(table: &mut Table, key: &str, value: Value) -> Result<()> {
+    let mut parts: Vec<&str> = key.split('.').collect();
+    let leaf = parts.pop().filter(|l| !l.is_empty()).context("Empty key")?;
+
+    let mut current = table;
+    for part in parts {
+        current = current
+            .entry(part)
+            .or_insert_with(|| Value::Table(Table::new()))
+            .as_table_mut()
+            .with_context(|| format!("`{}` is not a table", part))?;
+    }
+
+    current.insert(leaf.to_string(), value);
+    Ok(())
+}
+
+fn remove_path(table: &mut Table, key: &str) {
+    let mut parts: Vec<&str> = key.split('.').collect();
+    let Some(leaf) = parts.pop() else { return };
+
+    let mut current = table;
+    for part in parts {
+        match current.get_mut(part).and_then(Value::as_table_mut) {
+            Some(next) => current = next,
+            None => return,
+        }
+    }
+
+    current.remove(leaf);
+}
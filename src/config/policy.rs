@@ -0,0 +1,57 @@
+use anyhow::Result;
+use toml::{Table, Value};
+
+use super::types::Config;
+
+/// A key pinned by the repo policy to the value the repo gives it
+#[derive(Debug, Clone)]
+pub struct LockedKey {
+    pub key: String,
+    /// `None` when neither the repo nor the defaults set the key
+    pub value: Option<Value>,
+}
+
+/// Collect the keys locked by `[policy] locked` in the repo layer.
+///
+/// A locked key keeps the repo's value (or the built-in default when the repo
+/// does not set it), whatever the global config, profiles or overrides say.
+pub fn capture_locks(repo: Option<&Table>) -> Result<Vec<LockedKey>> {
+    let Some(repo) = repo else {
+        return Ok(vec![]);
+    };
+
+    let locked = repo
+        .get("policy")
+        .and_then(|p| p.get("locked"))
+        .and_then(Value::as_array)
+        .map(|keys| keys.iter().filter_map(Value::as_str).collect::<Vec<_>>())
+        .unwrap_or_default();
+
+    if locked.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let defaults = Value::try_from(Config::default())?;
+    let repo = Value::Table(repo.clone());
+
+    Ok(locked
+        .into_iter()
+        .map(|key| LockedKey {
+            key: key.to_string(),
+            value: lookup(&repo, key).or_else(|| lookup(&defaults, key)).cloned(),
+        })
+        .collect())
+}
+
+/// Whether changing `key` would touch a locked key
+pub fn is_locked(locks: &[LockedKey], key: &str) -> bool {
+    locks.iter().any(|lock| {
+        key == lock.key
+            || key.starts_with(&format!("{}.", lock.key))
+            || lock.key.starts_with(&format!("{}.", key))
+    })
+}
+
+fn lookup<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    key.split('.').try_fold(value, |current, part| current.get(part))
+}
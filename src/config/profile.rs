@@ -0,0 +1,138 @@
+use anyhow::Result;
+
+use super::overrides::apply_overrides;
+use super::types::{Config, EventConfig, ProfileConfig, DEFAULT_PROFILE};
+
+impl Config {
+    /// Settings for `event`, falling back to the built-in defaults
+    pub fn event(&self, event: &str) -> EventConfig {
+        let builtin = EventConfig::for_event(event);
+        let Some(config) = self.events.get(event) else {
+            return builtin;
+        };
+
+        let mut config = config.clone();
+        if config.template.is_empty() {
+            config.template = builtin.template;
+        }
+        if config.blocking.is_none() {
+            config.blocking = builtin.blocking;
+        }
+        if config.defer.is_none() {
+            config.defer = builtin.defer;
+        }
+        if config.trigger.is_none() {
+            config.trigger = builtin.trigger;
+        }
+        // A draft left on stdout never reaches the message file
+        if config.output.is_empty() && builtin.output.iter().any(|o| o == "commit_msg") {
+            config.output = builtin.output;
+        }
+        config
+    }
+
+    /// Git hooks to install for `events`, each once
+    pub fn hook_names(&self, events: &[String]) -> Vec<String> {
+        let mut hooks: Vec<String> = vec![];
+        for event in events {
+            let hook = self.event(event).hook(event).to_string();
+            if !hooks.contains(&hook) {
+                hooks.push(hook);
+            }
+        }
+        hooks
+    }
+
+    /// Name of the profile that applies to `event`.
+    ///
+    /// Precedence: `requested` (e.g. `--profile`), the event's `profile`,
+    /// the repo-level `profile`, then `global.default_profile`.
+    pub fn profile_for(&self, event: &str, requested: Option<&str>) -> Option<String> {
+        requested
+            .map(str::to_string)
+            .or_else(|| self.events.get(event).and_then(|e| e.profile.clone()))
+            .or_else(|| self.repo_profile())
+    }
+
+    /// Name of the profile selected for the repo as a whole
+    ///
+    /// An empty `default_profile` means no profile, and the built-in name only
+    /// applies when a profile of that name is defined; any other name must
+    /// exist, like every other reference.
+    pub fn repo_profile(&self) -> Option<String> {
+        self.profile.clone().or_else(|| {
+            let name = &self.global.default_profile;
+            let implicit = name.is_empty() || name == DEFAULT_PROFILE;
+            (!implicit || self.profiles.contains_key(name)).then(|| name.clone())
+        })
+    }
+
+    /// Return a copy of the config with the selected profile applied to `event`
+    pub fn with_profile(&self, event: &str, requested: Option<&str>) -> Result<Config> {
+        let mut config = self.clone();
+
+        if let Some(name) = self.profile_for(event, requested) {
+            let profile = self.find_profile(&name)?;
+            config.apply_shared(profile);
+            config.apply_event(event, profile);
+        }
+
+        // Overrides always win over profiles
+        apply_overrides(&config, &self.overrides)
+    }
+
+    /// Return a copy of the config with every configured event resolved
+    /// against its profile, and shared settings against the repo profile
+    pub fn resolved(&self) -> Result<Config> {
+        let mut config = self.clone();
+
+        if let Some(name) = self.repo_profile() {
+            config.apply_shared(self.find_profile(&name)?);
+        }
+
+        for event in self.events.keys() {
+            if let Some(name) = self.profile_for(event, None) {
+                config.apply_event(event, self.find_profile(&name)?);
+            }
+        }
+
+        apply_overrides(&config, &self.overrides)
+    }
+
+    fn find_profile(&self, name: &str) -> Result<&ProfileConfig> {
+        self.profiles
+            .get(name)
+            .ok_or_else(|| anyhow::anyhow!("Unknown profile '{}'", name))
+    }
+
+    /// Apply the parts of a profile that are not specific to an event
+    fn apply_shared(&mut self, profile: &ProfileConfig) {
+        if let Some(max_tokens) = profile.max_tokens {
+            self.context.max_tokens = max_tokens;
+        }
+        if let Some(rate_limit) = &profile.rate_limit {
+            self.rate_limit = rate_limit.clone();
+        }
+    }
+
+    /// Apply the event-level parts of a profile to `event`
+    fn apply_event(&mut self, event: &str, profile: &ProfileConfig) {
+        let mut event_config = self
+            .events
+            .get(event)
+            .cloned()
+            .unwrap_or_else(|| EventConfig::for_event(event));
+
+        if let Some(context) = &profile.context {
+            event_config.context = context.clone();
+        }
+        if let Some(template) = profile.templates.get(event) {
+            event_config.template = template.clone();
+        }
+        if let Some(output) = &profile.output {
+            event_config.output = output.clone();
+        }
+
+        self.events.insert(event.to_string(), event_config);
+    }
+}
@@ -0,0 +1,98 @@
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{ArrayValidation, InstanceType, Schema, SchemaObject};
+
+use super::types::Config;
+use super::validate::{
+    COMMIT_MSG_MODES, CONTEXT_LEVELS, CONTEXT_STRATEGIES, FILE_FORMATS, KNOWN_OUTPUTS, LIST_MERGE, LOG_LEVELS,
+    RATE_LIMIT_STRATEGIES, TAG_TRIGGERS, URGENCIES,
+};
+
+/// JSON Schema for `config.toml`, for editor completion and validation
+pub fn config_schema() -> serde_json::Value {
+    let mut schema = schemars::schema_for!(Config);
+    schema.schema.metadata().title = Some("gitclaude configuration".to_string());
+
+    serde_json::to_value(schema).unwrap_or_default()
+}
+
+fn string_enum(values: &[&str], nullable: bool) -> SchemaObject {
+    let mut enum_values: Vec<serde_json::Value> = values.iter().map(|v| (*v).into()).collect();
+    let instance_type = if nullable {
+        enum_values.push(serde_json::Value::Null);
+        vec![InstanceType::String, InstanceType::Null].into()
+    } else {
+        InstanceType::String.into()
+    };
+
+    SchemaObject {
+        instance_type: Some(instance_type),
+        enum_values: Some(enum_values),
+        ..Default::default()
+    }
+}
+
+fn string_enum_array(values: &[&str], nullable: bool) -> Schema {
+    let instance_type = if nullable {
+        vec![InstanceType::Array, InstanceType::Null].into()
+    } else {
+        InstanceType::Array.into()
+    };
+
+    SchemaObject {
+        instance_type: Some(instance_type),
+        array: Some(Box::new(ArrayValidation {
+            items: Some(Schema::Object(string_enum(values, false)).into()),
+            ..Default::default()
+        })),
+        ..Default::default()
+    }
+    .into()
+}
+
+pub(super) fn context_level(_: &mut SchemaGenerator) -> Schema {
+    string_enum(CONTEXT_LEVELS, false).into()
+}
+
+pub(super) fn optional_context_level(_: &mut SchemaGenerator) -> Schema {
+    string_enum(CONTEXT_LEVELS, true).into()
+}
+
+pub(super) fn context_strategy(_: &mut SchemaGenerator) -> Schema {
+    string_enum(CONTEXT_STRATEGIES, false).into()
+}
+
+pub(super) fn rate_limit_strategy(_: &mut SchemaGenerator) -> Schema {
+    string_enum(RATE_LIMIT_STRATEGIES, false).into()
+}
+
+pub(super) fn urgency(_: &mut SchemaGenerator) -> Schema {
+    string_enum(URGENCIES, false).into()
+}
+
+pub(super) fn commit_msg_mode(_: &mut SchemaGenerator) -> Schema {
+    string_enum(COMMIT_MSG_MODES, false).into()
+}
+
+pub(super) fn tag_trigger(_: &mut SchemaGenerator) -> Schema {
+    string_enum(TAG_TRIGGERS, true).into()
+}
+
+pub(super) fn file_format(_: &mut SchemaGenerator) -> Schema {
+    string_enum(FILE_FORMATS, false).into()
+}
+
+pub(super) fn log_level(_: &mut SchemaGenerator) -> Schema {
+    string_enum(LOG_LEVELS, false).into()
+}
+
+pub(super) fn list_merge(_: &mut SchemaGenerator) -> Schema {
+    string_enum(LIST_MERGE, false).into()
+}
+
+pub(super) fn outputs(_: &mut SchemaGenerator) -> Schema {
+    string_enum_array(KNOWN_OUTPUTS, false)
+}
+
+pub(super) fn optional_outputs(_: &mut SchemaGenerator) -> Schema {
+    string_enum_array(KNOWN_OUTPUTS, true)
+}
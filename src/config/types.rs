@@ -1,9 +1,20 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+use super::migrate::CONFIG_VERSION;
+use super::overrides::Override;
+use super::policy::LockedKey;
+use super::schema;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
 pub struct Config {
+    /// Config file layout version, see `gitclaude config migrate`
+    pub version: u32,
+    /// Profile selected for this repo (overrides `global.default_profile`)
+    pub profile: Option<String>,
     pub general: GeneralConfig,
     pub global: GlobalConfig,
     pub events: HashMap<String, EventConfig>,
@@ -13,18 +24,61 @@ pub struct Config {
     pub monorepo: MonorepoConfig,
     pub templates: TemplatesConfig,
     pub claude: ClaudeConfig,
+    pub merge: MergeConfig,
+    pub profiles: HashMap<String, ProfileConfig>,
+    /// Other config files merged beneath this one, relative to this file
+    pub include: Vec<String>,
+    pub policy: PolicyConfig,
+    /// Environment and `-c` overrides applied on top of the files
+    #[serde(skip)]
+    pub overrides: Vec<Override>,
+    /// Keys pinned by the repo policy
+    #[serde(skip)]
+    pub locks: Vec<LockedKey>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            version: CONFIG_VERSION,
+            profile: None,
+            general: GeneralConfig::default(),
+            global: GlobalConfig::default(),
+            events: HashMap::new(),
+            context: ContextConfig::default(),
+            rate_limit: RateLimitConfig::default(),
+            output: OutputConfig::default(),
+            monorepo: MonorepoConfig::default(),
+            templates: TemplatesConfig::default(),
+            claude: ClaudeConfig::default(),
+            merge: MergeConfig::default(),
+            profiles: HashMap::new(),
+            include: vec![],
+            policy: PolicyConfig::default(),
+            overrides: vec![],
+            locks: vec![],
+        }
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct GeneralConfig {
-    #[serde(default = "default_true")]
+    /// Run Claude asynchronously (non-blocking)
+    #[serde(default = "default_true", alias = "async")]
     pub async_mode: bool,
+    /// Enable desktop notifications
     #[serde(default = "default_true")]
     pub notify: bool,
+    /// Save responses to files
     #[serde(default)]
     pub log_responses: bool,
+    /// Log level
     #[serde(default = "default_log_level")]
+    #[schemars(schema_with = "schema::log_level")]
     pub log_level: String,
+    /// Maximum number of an event's actions running at once
+    #[serde(default = "default_max_parallel_actions")]
+    pub max_parallel_actions: usize,
 }
 
 impl Default for GeneralConfig {
@@ -34,38 +88,199 @@ impl Default for GeneralConfig {
             notify: true,
             log_responses: false,
             log_level: "info".to_string(),
+            max_parallel_actions: default_max_parallel_actions(),
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+/// Built-in `global.default_profile`; it may be left undefined
+pub const DEFAULT_PROFILE: &str = "standard";
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct GlobalConfig {
+    /// Listen to git events in all repos
     #[serde(default)]
     pub listen_globally: bool,
+    /// Profile used when none is selected
     #[serde(default = "default_profile")]
     pub default_profile: String,
+    /// Repos to ignore even if global listening is enabled
     #[serde(default)]
     pub ignore_repos: Vec<String>,
+    /// Only act in repos matching one of these patterns (empty = all).
+    /// Patterns match the repo path (`~/work/*`) or a remote (`github.com/acme/*`)
+    #[serde(default)]
+    pub allow_repos: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl Default for GlobalConfig {
+    fn default() -> Self {
+        Self {
+            listen_globally: false,
+            default_profile: default_profile(),
+            ignore_repos: vec![],
+            allow_repos: vec![],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct EventConfig {
+    /// Whether the event triggers Claude
     #[serde(default = "default_true")]
     pub enabled: bool,
+    /// Template name; empty means the built-in default for the event
+    #[serde(default)]
     pub template: String,
+    /// Context level
     #[serde(default = "default_context")]
+    #[schemars(schema_with = "schema::context_level")]
     pub context: String,
+    /// Output handlers for the response
     #[serde(default)]
+    #[schemars(schema_with = "schema::outputs")]
     pub output: Vec<String>,
+    /// Run synchronously and allow aborting the git operation.
+    /// Defaults to on for pre-commit
+    #[serde(default)]
+    pub blocking: Option<bool>,
+    /// When blocking, let git carry on if Claude times out or fails
+    #[serde(default = "default_true")]
+    pub fail_open: bool,
+    /// Profile used for this event only
+    #[serde(default)]
+    pub profile: Option<String>,
+    /// Run Claude after git finishes instead of holding it up.
+    /// Defaults to on for pre-push and tag
+    #[serde(default)]
+    pub defer: Option<bool>,
+    /// `tag` only: run when a tag is created or when it is pushed
+    #[serde(default)]
+    #[schemars(schema_with = "schema::tag_trigger")]
+    pub trigger: Option<String>,
+    /// Only run when all of these conditions hold
+    #[serde(default)]
+    pub when: WhenConfig,
+    /// Separate Claude runs for this event; replaces `template` when set
     #[serde(default)]
-    pub blocking: bool,
+    pub actions: Vec<ActionConfig>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl EventConfig {
+    /// Built-in settings for an event that has no config section
+    pub fn for_event(event: &str) -> Self {
+        let template = match event {
+            "pre-push" => "changelog",
+            "pre-commit" => "validate",
+            "post-merge" => "summary",
+            "post-checkout" => "context",
+            "post-rewrite" => "rewrite",
+            "prepare-commit-msg" => "commit-message",
+            "tag" => "release-notes",
+            _ => "review",
+        };
+
+        Self {
+            enabled: true,
+            template: template.to_string(),
+            context: default_context(),
+            output: vec![if event == "prepare-commit-msg" { "commit_msg" } else { "notify" }.to_string()],
+            blocking: Some(event == "pre-commit"),
+            fail_open: true,
+            profile: None,
+            defer: Some(matches!(event, "pre-push" | "tag")),
+            trigger: (event == "tag").then(|| "create".to_string()),
+            when: WhenConfig::default(),
+            actions: vec![],
+        }
+    }
+
+    /// The git hook that fires `event`. `tag` has no hook of its own and is
+    /// picked up from `reference-transaction`, or `pre-push` with
+    /// `trigger = "push"`
+    pub fn hook<'a>(&self, event: &'a str) -> &'a str {
+        match (event, self.trigger.as_deref()) {
+            ("tag", Some("push")) => "pre-push",
+            ("tag", _) => "reference-transaction",
+            _ => event,
+        }
+    }
+}
+
+/// One of several Claude runs triggered by an event
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ActionConfig {
+    /// Name used in reports and output files; defaults to the template name
+    #[serde(default)]
+    pub name: String,
+    /// Template name
+    pub template: String,
+    /// Context level; defaults to the event's
+    #[serde(default)]
+    #[schemars(schema_with = "schema::optional_context_level")]
+    pub context: Option<String>,
+    /// Output handlers; default to the event's
+    #[serde(default)]
+    #[schemars(schema_with = "schema::optional_outputs")]
+    pub output: Option<Vec<String>>,
+    /// Rate-limit bucket; defaults to the action name
+    #[serde(default)]
+    pub bucket: Option<String>,
+}
+
+/// Conditions an event must meet before Claude is invoked.
+///
+/// Each list matches if any of its patterns does; empty lists and unset
+/// fields always match.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct WhenConfig {
+    /// Branch globs, e.g. `["main", "release/*"]`
+    #[serde(default)]
+    pub branches: Vec<String>,
+    /// Globs of which at least one changed file must match, e.g. `["src/**"]`
+    #[serde(default)]
+    pub paths: Vec<String>,
+    /// Author name or email globs, case-insensitive
+    #[serde(default)]
+    pub authors: Vec<String>,
+    /// Minimum number of changed lines (insertions + deletions)
+    #[serde(default)]
+    pub min_lines: Option<usize>,
+    /// Regexes matched against the commit message
+    #[serde(default)]
+    pub message: Vec<String>,
+}
+
+/// A named bundle of settings that can be selected per repo, event or run
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct ProfileConfig {
+    /// Context level: minimal, standard, extended, full
+    #[serde(default)]
+    #[schemars(schema_with = "schema::optional_context_level")]
+    pub context: Option<String>,
+    /// Approximate token budget for context
+    #[serde(default)]
+    pub max_tokens: Option<usize>,
+    /// Template per event, e.g. `post-commit = "review"`
+    #[serde(default)]
+    pub templates: HashMap<String, String>,
+    /// Replaces the `[rate_limit]` section when set
+    #[serde(default)]
+    pub rate_limit: Option<RateLimitConfig>,
+    /// Output handlers for the response
+    #[serde(default)]
+    #[schemars(schema_with = "schema::optional_outputs")]
+    pub output: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ContextConfig {
+    /// Approximate token budget for context
     #[serde(default = "default_max_tokens")]
     pub max_tokens: usize,
+    /// Context strategy
     #[serde(default = "default_strategy")]
+    #[schemars(schema_with = "schema::context_strategy")]
     pub strategy: String,
     #[serde(default)]
     pub smart: SmartContextConfig,
@@ -84,36 +299,48 @@ impl Default for ContextConfig {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct SmartContextConfig {
+    /// Always include these file patterns regardless of priority
     #[serde(default)]
     pub always_include: Vec<String>,
+    /// Priority by file extension (higher = more important)
     #[serde(default)]
     pub priority_by_extension: HashMap<String, u8>,
+    /// Include diff --stat for overview
     #[serde(default = "default_true")]
     pub include_diff_stat: bool,
+    /// Maximum diff lines before truncation
     #[serde(default = "default_truncate_at")]
     pub truncate_at: usize,
+    /// Include N recent commits for context
     #[serde(default = "default_recent_commits")]
     pub include_recent_commits: usize,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct ExcludeConfig {
+    /// Patterns to always exclude from context
     #[serde(default)]
     pub patterns: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct RateLimitConfig {
+    /// Rate limiting strategy
     #[serde(default = "default_rate_strategy")]
+    #[schemars(schema_with = "schema::rate_limit_strategy")]
     pub strategy: String,
+    /// Debounce: wait this many seconds after last commit
     #[serde(default = "default_debounce")]
     pub debounce_seconds: u64,
+    /// Batch: collect commits for this window
     #[serde(default = "default_batch_window")]
     pub batch_window_seconds: u64,
+    /// Cooldown: minimum minutes between runs
     #[serde(default = "default_cooldown")]
     pub cooldown_minutes: u64,
+    /// Maximum runs per hour (0 = unlimited)
     #[serde(default = "default_max_runs")]
     pub max_runs_per_hour: u32,
 }
@@ -130,7 +357,7 @@ impl Default for RateLimitConfig {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct OutputConfig {
     #[serde(default)]
     pub notify: NotifyConfig,
@@ -144,14 +371,22 @@ pub struct OutputConfig {
     pub clipboard: ClipboardConfig,
     #[serde(default)]
     pub git_note: GitNoteConfig,
+    #[serde(default)]
+    pub commit_msg: CommitMsgConfig,
+    #[serde(default)]
+    pub changelog: ChangelogConfig,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct NotifyConfig {
+    /// Notification urgency
     #[serde(default = "default_urgency")]
+    #[schemars(schema_with = "schema::urgency")]
     pub urgency: String,
+    /// Timeout in milliseconds (0 = persistent)
     #[serde(default = "default_timeout")]
     pub timeout: u32,
+    /// App name shown in notification
     #[serde(default = "default_app_name")]
     pub app_name: String,
 }
@@ -166,12 +401,16 @@ impl Default for NotifyConfig {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct FileOutputConfig {
+    /// Directory for saved responses (relative to repo or absolute)
     #[serde(default = "default_file_path")]
     pub path: PathBuf,
+    /// File format
     #[serde(default = "default_format")]
+    #[schemars(schema_with = "schema::file_format")]
     pub format: String,
+    /// Include timestamp in filename
     #[serde(default = "default_true")]
     pub timestamp: bool,
 }
@@ -186,33 +425,40 @@ impl Default for FileOutputConfig {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct TerminalConfig {
+    /// Automatically open terminal with response
     #[serde(default)]
     pub auto_open: bool,
+    /// Terminal emulator to use
     #[serde(default = "default_terminal")]
     pub terminal: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct SessionConfig {
+    /// Open interactive Claude session after response
     #[serde(default)]
     pub auto_open: bool,
+    /// Working directory: repo, home, or specific path
     #[serde(default = "default_working_dir")]
     pub working_dir: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct ClipboardConfig {
+    /// Copy response to clipboard
     #[serde(default)]
     pub enabled: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct GitNoteConfig {
+    /// Add response as git note
     #[serde(default)]
     pub enabled: bool,
-    #[serde(default = "default_note_ref")]
+    /// Notes ref to write to
+    #[serde(default = "default_note_ref", alias = "ref")]
     pub note_ref: String,
 }
 
@@ -225,14 +471,49 @@ impl Default for GitNoteConfig {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CommitMsgConfig {
+    /// How a drafted message is written: comment or message
+    #[serde(default = "default_commit_msg_mode")]
+    #[schemars(schema_with = "schema::commit_msg_mode")]
+    pub mode: String,
+}
+
+impl Default for CommitMsgConfig {
+    fn default() -> Self {
+        Self {
+            mode: "comment".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ChangelogConfig {
+    /// File release notes drafts are written to (relative to repo or absolute)
+    #[serde(default = "default_changelog_path")]
+    pub path: PathBuf,
+}
+
+impl Default for ChangelogConfig {
+    fn default() -> Self {
+        Self {
+            path: PathBuf::from("CHANGELOG.md"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct MonorepoConfig {
+    /// Enable monorepo detection
     #[serde(default = "default_true")]
     pub enabled: bool,
+    /// Automatically detect affected packages
     #[serde(default = "default_true")]
     pub detect_affected: bool,
+    /// Only send context from affected packages
     #[serde(default = "default_true")]
     pub scope_context: bool,
+    /// Package directories to look for
     #[serde(default = "default_package_dirs")]
     pub package_dirs: Vec<String>,
 }
@@ -253,10 +534,12 @@ impl Default for MonorepoConfig {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TemplatesConfig {
+    /// Directory for custom templates
     #[serde(default = "default_templates_dir")]
     pub directory: PathBuf,
+    /// Fall back to built-in templates if custom not found
     #[serde(default = "default_true")]
     pub fallback_builtin: bool,
 }
@@ -270,14 +553,19 @@ impl Default for TemplatesConfig {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ClaudeConfig {
+    /// Claude CLI binary path (default: find in PATH)
     pub binary: Option<PathBuf>,
+    /// Additional arguments to pass to claude
     #[serde(default)]
     pub extra_args: Vec<String>,
+    /// Timeout for Claude response in seconds
     #[serde(default = "default_claude_timeout")]
     pub timeout: u64,
+    /// Output format
     #[serde(default = "default_format")]
+    #[schemars(schema_with = "schema::file_format")]
     pub output_format: String,
 }
 
@@ -292,10 +580,57 @@ impl Default for ClaudeConfig {
     }
 }
 
+/// Team policy enforced by the repo config
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct PolicyConfig {
+    /// Dotted keys the global config, profiles and overrides cannot change.
+    /// Only honoured in the repo config and its includes.
+    #[serde(default)]
+    pub locked: Vec<String>,
+}
+
+/// How layered config files are combined
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MergeConfig {
+    /// Default behaviour for lists: "replace" or "append"
+    #[serde(default = "default_list_merge")]
+    #[schemars(schema_with = "schema::list_merge")]
+    pub lists: String,
+    /// Dotted keys whose lists are always appended
+    #[serde(default)]
+    pub append: Vec<String>,
+    /// Dotted keys whose lists are always replaced
+    #[serde(default)]
+    pub replace: Vec<String>,
+}
+
+impl Default for MergeConfig {
+    fn default() -> Self {
+        Self {
+            lists: "replace".to_string(),
+            append: vec![],
+            replace: vec![],
+        }
+    }
+}
+
+impl MergeConfig {
+    /// Whether the list at `key` should be appended to rather than replaced
+    pub fn appends(&self, key: &str) -> bool {
+        if self.replace.iter().any(|k| k == key) {
+            return false;
+        }
+        if self.append.iter().any(|k| k == key) {
+            return true;
+        }
+        self.lists == "append"
+    }
+}
+
 // Default value helpers
 fn default_true() -> bool { true }
 fn default_log_level() -> String { "info".to_string() }
-fn default_profile() -> String { "standard".to_string() }
+fn default_profile() -> String { DEFAULT_PROFILE.to_string() }
 fn default_context() -> String { "standard".to_string() }
 fn default_max_tokens() -> usize { 4000 }
 fn default_strategy() -> String { "smart".to_string() }
@@ -314,8 +649,12 @@ fn default_format() -> String { "markdown".to_string() }
 fn default_terminal() -> String { "alacritty".to_string() }
 fn default_working_dir() -> String { "repo".to_string() }
 fn default_note_ref() -> String { "refs/notes/claude".to_string() }
+fn default_commit_msg_mode() -> String { "comment".to_string() }
+fn default_changelog_path() -> PathBuf { PathBuf::from("CHANGELOG.md") }
 fn default_package_dirs() -> Vec<String> {
     vec!["packages".to_string(), "apps".to_string(), "libs".to_string(), "crates".to_string()]
 }
 fn default_templates_dir() -> PathBuf { PathBuf::from("~/.config/gitclaude/templates/") }
 fn default_claude_timeout() -> u64 { 120 }
+fn default_max_parallel_actions() -> usize { 2 }
+fn default_list_merge() -> String { "replace".to_string() }
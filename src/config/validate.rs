@@ -0,0 +1,421 @@
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use toml_edit::{ImDocument, Item, TableLike};
+
+use super::loader::resolve_include;
+use super::migrate::CONFIG_VERSION;
+use super::types::{Config, DEFAULT_PROFILE};
+use crate::rate_limit::DEFAULT_BUCKET;
+use crate::templates::template_exists;
+
+/// Git events gitclaude knows how to handle
+pub const KNOWN_EVENTS: &[&str] = &[
+    "post-commit",
+    "pre-push",
+    "pre-commit",
+    "post-merge",
+    "post-checkout",
+    "post-rewrite",
+    "prepare-commit-msg",
+    "tag",
+];
+
+/// Output handlers that can be listed in an event's `output`
+pub const KNOWN_OUTPUTS: &[&str] = &["notify", "file", "terminal", "git_note", "commit_msg", "changelog"];
+
+pub const CONTEXT_LEVELS: &[&str] = &["minimal", "standard", "extended", "full"];
+pub const CONTEXT_STRATEGIES: &[&str] = &["minimal", "standard", "extended", "full", "smart"];
+pub const RATE_LIMIT_STRATEGIES: &[&str] = &["debounce", "batch", "cooldown", "smart", "none"];
+pub const URGENCIES: &[&str] = &["low", "normal", "critical"];
+pub const FILE_FORMATS: &[&str] = &["markdown", "json", "plain", "txt"];
+pub const LOG_LEVELS: &[&str] = &["trace", "debug", "info", "warn", "error"];
+pub const COMMIT_MSG_MODES: &[&str] = &["comment", "message"];
+pub const TAG_TRIGGERS: &[&str] = &["create", "push"];
+pub const LIST_MERGE: &[&str] = &["replace", "append"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A problem found in a config file
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub path: PathBuf,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(
+            f,
+            "{}:{}:{}: {}: {}",
+            self.path.display(),
+            self.line,
+            self.column,
+            label,
+            self.message
+        )
+    }
+}
+
+/// Validate a single config file.
+///
+/// `merged` is the fully merged config, used to check references such as
+/// templates and profiles that may be defined in another layer.
+pub fn validate_file(path: &Path, content: &str, merged: Option<&Config>) -> Vec<Diagnostic> {
+    let mut checker = Checker {
+        path,
+        content,
+        diagnostics: vec![],
+    };
+
+    let doc = match ImDocument::parse(content) {
+        Ok(doc) => doc,
+        Err(e) => {
+            checker.push(Severity::Error, e.span(), e.message().to_string());
+            return checker.diagnostics;
+        }
+    };
+
+    let version_item = doc.get("version");
+    let version = version_item.and_then(Item::as_integer).unwrap_or(1);
+    let version_span = version_item.and_then(Item::span);
+
+    if version < CONFIG_VERSION as i64 {
+        checker.push(
+            Severity::Warning,
+            version_span,
+            format!(
+                "config version {} is outdated (current is {}), run `gitclaude config migrate`",
+                version, CONFIG_VERSION
+            ),
+        );
+    } else if version > CONFIG_VERSION as i64 {
+        checker.push(
+            Severity::Error,
+            version_span,
+            format!(
+                "config version {} is newer than this gitclaude supports ({})",
+                version, CONFIG_VERSION
+            ),
+        );
+    }
+
+    // Type errors and unknown keys come straight from deserialization
+    let mut ignored = vec![];
+    let deserializer = toml::Deserializer::new(content);
+    let result: Result<Config, _> = serde_ignored::deserialize(deserializer, |key| {
+        ignored.push(key.to_string());
+    });
+
+    if let Err(e) = result {
+        checker.push(Severity::Error, e.span(), e.message().to_string());
+    }
+
+    for key in ignored {
+        let span = find_key_span(&doc, &key);
+        checker.push(Severity::Warning, span, format!("unknown key `{}`", key));
+    }
+
+    checker.check_values(&doc, merged);
+
+    checker.diagnostics
+}
+
+struct Checker<'a> {
+    path: &'a Path,
+    content: &'a str,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Checker<'_> {
+    fn push(&mut self, severity: Severity, span: Option<Range<usize>>, message: String) {
+        let (line, column) = span
+            .map(|s| line_column(self.content, s.start))
+            .unwrap_or((1, 1));
+
+        self.diagnostics.push(Diagnostic {
+            severity,
+            path: self.path.to_path_buf(),
+            line,
+            column,
+            message,
+        });
+    }
+
+    /// Check that a string value is one of `allowed`
+    fn check_enum(&mut self, item: Option<&Item>, key: &str, allowed: &[&str]) {
+        let Some(item) = item else { return };
+        let Some(value) = item.as_str() else { return };
+
+        if !allowed.contains(&value) {
+            self.push(
+                Severity::Error,
+                item.span(),
+                format!(
+                    "invalid value \"{}\" for `{}`, expected one of: {}",
+                    value,
+                    key,
+                    allowed.join(", ")
+                ),
+            );
+        }
+    }
+
+    fn check_values(&mut self, doc: &ImDocument<&str>, merged: Option<&Config>) {
+        let root = doc.as_table();
+
+        self.check_enum(lookup_in(root, "general.log_level"), "general.log_level", LOG_LEVELS);
+        self.check_enum(lookup_in(root, "context.strategy"), "context.strategy", CONTEXT_STRATEGIES);
+        self.check_enum(lookup_in(root, "rate_limit.strategy"), "rate_limit.strategy", RATE_LIMIT_STRATEGIES);
+        self.check_enum(lookup_in(root, "output.notify.urgency"), "output.notify.urgency", URGENCIES);
+        self.check_enum(lookup_in(root, "output.file.format"), "output.file.format", FILE_FORMATS);
+        self.check_enum(lookup_in(root, "output.commit_msg.mode"), "output.commit_msg.mode", COMMIT_MSG_MODES);
+        self.check_enum(lookup_in(root, "claude.output_format"), "claude.output_format", FILE_FORMATS);
+        self.check_enum(lookup_in(root, "merge.lists"), "merge.lists", LIST_MERGE);
+
+        if let Some(item) = root.get("profile") {
+            self.check_profile_ref(item, "profile", merged);
+        }
+        if let Some(item) = lookup_in(root, "global.default_profile") {
+            // Empty or the built-in name may stay undefined, meaning "no profile"
+            if !matches!(item.as_str(), Some("") | Some(DEFAULT_PROFILE)) {
+                self.check_profile_ref(item, "global.default_profile", merged);
+            }
+        }
+
+        if let Some(includes) = root.get("include").and_then(Item::as_array) {
+            for include in includes.iter() {
+                let Some(name) = include.as_str() else { continue };
+                let path = resolve_include(self.path, name);
+                if !path.exists() {
+                    self.push(
+                        Severity::Error,
+                        include.span(),
+                        format!("included file {} not found", path.display()),
+                    );
+                }
+            }
+        }
+
+        if let Some(events) = root.get("events").and_then(Item::as_table_like) {
+            for (name, event) in events.iter() {
+                let Some(event) = event.as_table_like() else { continue };
+
+                if !KNOWN_EVENTS.contains(&name) {
+                    let span = events.get_key_value(name).and_then(|(k, _)| k.span());
+                    let message = if name == "post-push" {
+                        "`post-push` is not a git hook and never fires, use `pre-push` \
+                         (`gitclaude config migrate` renames it)"
+                            .to_string()
+                    } else {
+                        format!("unknown event `{}`", name)
+                    };
+                    self.push(Severity::Warning, span, message);
+                }
+
+                let prefix = format!("events.{}", name);
+                self.check_enum(event.get("context"), &format!("{}.context", prefix), CONTEXT_LEVELS);
+                self.check_outputs(event.get("output"), &prefix);
+                self.check_enum(event.get("trigger"), &format!("{}.trigger", prefix), TAG_TRIGGERS);
+
+                if let Some(item) = event.get("template") {
+                    self.check_template_ref(item, merged);
+                }
+                if let Some(item) = event.get("profile") {
+                    self.check_profile_ref(item, &format!("{}.profile", prefix), merged);
+                }
+                if let Some(when) = event.get("when").and_then(Item::as_table_like) {
+                    self.check_when(when, &format!("{}.when", prefix));
+                }
+                if let Some(actions) = event.get("actions").and_then(Item::as_array_of_tables) {
+                    for (i, action) in actions.iter().enumerate() {
+                        let action_prefix = format!("{}.actions[{}]", prefix, i);
+                        self.check_enum(action.get("context"), &format!("{}.context", action_prefix), CONTEXT_LEVELS);
+                        self.check_outputs(action.get("output"), &action_prefix);
+                        if let Some(item) = action.get("template") {
+                            self.check_template_ref(item, merged);
+                        }
+                        self.check_bucket(action, &action_prefix);
+                    }
+                }
+            }
+        }
+
+        if let Some(profiles) = root.get("profiles").and_then(Item::as_table_like) {
+            for (name, profile) in profiles.iter() {
+                let Some(profile) = profile.as_table_like() else { continue };
+
+                let prefix = format!("profiles.{}", name);
+                self.check_enum(profile.get("context"), &format!("{}.context", prefix), CONTEXT_LEVELS);
+                self.check_outputs(profile.get("output"), &prefix);
+                self.check_enum(
+                    lookup_in(profile, "rate_limit.strategy"),
+                    &format!("{}.rate_limit.strategy", prefix),
+                    RATE_LIMIT_STRATEGIES,
+                );
+
+                if let Some(templates) = profile.get("templates").and_then(Item::as_table_like) {
+                    for (_, item) in templates.iter() {
+                        self.check_template_ref(item, merged);
+                    }
+                }
+            }
+        }
+    }
+
+    fn check_outputs(&mut self, item: Option<&Item>, prefix: &str) {
+        let Some(outputs) = item.and_then(Item::as_array) else { return };
+
+        for output in outputs.iter() {
+            let Some(name) = output.as_str() else { continue };
+            if !KNOWN_OUTPUTS.contains(&name) {
+                self.push(
+                    Severity::Error,
+                    output.span(),
+                    format!(
+                        "unknown output \"{}\" in `{}.output`, expected one of: {}",
+                        name,
+                        prefix,
+                        KNOWN_OUTPUTS.join(", ")
+                    ),
+                );
+            }
+        }
+    }
+
+    /// The event's own rate-limit bucket is reserved, an action sharing it
+    /// would be throttled together with the event and labelled like it
+    fn check_bucket(&mut self, action: &dyn TableLike, prefix: &str) {
+        let Some((key, item)) = ["bucket", "name"]
+            .into_iter()
+            .find_map(|key| action.get(key).map(|item| (key, item)))
+        else {
+            return;
+        };
+
+        if item.as_str() == Some(DEFAULT_BUCKET) {
+            self.push(
+                Severity::Error,
+                item.span(),
+                format!(
+                    "`{}.{}` uses the reserved rate-limit bucket \"{}\", pick another {}",
+                    prefix, key, DEFAULT_BUCKET, key
+                ),
+            );
+        }
+    }
+
+    /// Check that `when` globs and regexes compile
+    fn check_when(&mut self, when: &dyn TableLike, prefix: &str) {
+        for key in ["branches", "paths", "authors"] {
+            let Some(patterns) = when.get(key).and_then(Item::as_array) else { continue };
+
+            for pattern in patterns.iter() {
+                let Some(raw) = pattern.as_str() else { continue };
+                if let Err(e) = glob::Pattern::new(raw) {
+                    self.push(
+                        Severity::Error,
+                        pattern.span(),
+                        format!("invalid glob \"{}\" in `{}.{}`: {}", raw, prefix, key, e),
+                    );
+                }
+            }
+        }
+
+        let Some(patterns) = when.get("message").and_then(Item::as_array) else { return };
+        for pattern in patterns.iter() {
+            let Some(raw) = pattern.as_str() else { continue };
+            if let Err(e) = regex::Regex::new(raw) {
+                // regex errors span several lines; the last one says what is wrong
+                let e = e.to_string();
+                let reason = e.lines().last().unwrap_or_default().trim_start_matches("error: ");
+                self.push(
+                    Severity::Error,
+                    pattern.span(),
+                    format!("invalid regex \"{}\" in `{}.message`: {}", raw, prefix, reason),
+                );
+            }
+        }
+    }
+
+    fn check_template_ref(&mut self, item: &Item, merged: Option<&Config>) {
+        let (Some(name), Some(config)) = (item.as_str(), merged) else { return };
+
+        if !name.is_empty() && !template_exists(name, &config.templates) {
+            self.push(
+                Severity::Error,
+                item.span(),
+                format!(
+                    "template \"{}\" not found in {} or built-ins",
+                    name,
+                    config.templates.directory.display()
+                ),
+            );
+        }
+    }
+
+    fn check_profile_ref(&mut self, item: &Item, key: &str, merged: Option<&Config>) {
+        let (Some(name), Some(config)) = (item.as_str(), merged) else { return };
+
+        if !config.profiles.contains_key(name) {
+            self.push(
+                Severity::Error,
+                item.span(),
+                format!("`{}` refers to undefined profile \"{}\"", key, name),
+            );
+        }
+    }
+}
+
+/// Look up a dotted key in a table
+fn lookup_in<'a>(table: &'a dyn TableLike, key: &str) -> Option<&'a Item> {
+    let mut parts = key.split('.');
+    let mut item = table.get(parts.next()?)?;
+
+    for part in parts {
+        item = item.as_table_like()?.get(part)?;
+    }
+
+    Some(item)
+}
+
+/// Span of the deepest key of a dotted path that exists in the document
+fn find_key_span(doc: &ImDocument<&str>, key: &str) -> Option<Range<usize>> {
+    let mut table: &dyn TableLike = doc.as_table();
+    let mut span = None;
+
+    for part in key.split('.') {
+        let (k, item) = table.get_key_value(part)?;
+        span = k.span().or_else(|| item.span()).or(span);
+
+        match item.as_table_like() {
+            Some(next) => table = next,
+            None => break,
+        }
+    }
+
+    span
+}
+
+/// Convert a byte offset into a 1-based line and column
+fn line_column(content: &str, offset: usize) -> (usize, usize) {
+    let before = &content[..offset.min(content.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before
+        .rfind('\n')
+        .map(|i| before[i + 1..].chars().count() + 1)
+        .unwrap_or_else(|| before.chars().count() + 1);
+
+    (line, column)
+}
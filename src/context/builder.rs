@@ -12,21 +12,45 @@ pub enum ContextLevel {
     Full,      // Full repo context
 }
 
+impl ContextLevel {
+    /// Parse a level name from config, defaulting to standard
+    pub fn parse(name: &str) -> Self {
+        match name {
+            "minimal" => ContextLevel::Minimal,
+            "extended" => ContextLevel::Extended,
+            "full" => ContextLevel::Full,
+            _ => ContextLevel::Standard,
+        }
+    }
+}
+
 /// Built context ready for template rendering
 #[derive(Debug, Clone)]
 pub struct Context {
     pub commit_hash: String,
     pub commit_message: String,
     pub author: String,
+    pub author_email: String,
     pub date: String,
     pub branch: String,
     pub diff: String,
     pub diff_stat: String,
+    pub lines_changed: usize,
     pub staged_diff: Option<String>,
     pub staged_count: Option<usize>,
     pub affected_files: Vec<String>,
     pub affected_packages: Vec<String>,
     pub recent_commits: Vec<CommitInfo>,
+    /// Commits the event is about, e.g. those being pushed
+    pub commits: Vec<CommitInfo>,
+    /// How many commits the event is about; `commits` may list fewer
+    pub commit_count: usize,
+    /// The merge post-merge runs for
+    pub merge: Option<super::merge::MergeInfo>,
+    /// Briefing for the branch post-checkout switched to
+    pub checkout: Option<super::checkout::CheckoutInfo>,
+    /// The release a tag event runs for
+    pub release: Option<super::release::ReleaseInfo>,
 }
 
 #[derive(Debug, Clone)]
@@ -35,6 +59,39 @@ pub struct CommitInfo {
     pub message: String,
     pub author: String,
     pub date: String,
+    /// Empty unless the event includes per-commit diffs
+    pub diff: String,
+}
+
+impl CommitInfo {
+    /// Summarize a commit, including its diff when `diff_config` is given
+    pub fn from_commit(
+        repo: &git2::Repository,
+        commit: &git2::Commit,
+        diff_config: Option<&Config>,
+    ) -> Result<Self> {
+        let diff = match diff_config {
+            Some(config) => super::diff::get_commit_diff(repo, commit, config)?.0,
+            None => String::new(),
+        };
+
+        Ok(Self {
+            hash: commit.id().to_string()[..7].to_string(),
+            message: commit.message().unwrap_or("").lines().next().unwrap_or("").to_string(),
+            author: commit.author().name().unwrap_or("Unknown").to_string(),
+            date: chrono::DateTime::from_timestamp(commit.time().seconds(), 0)
+                .map(|dt| dt.format("%Y-%m-%d").to_string())
+                .unwrap_or_default(),
+            diff,
+        })
+    }
+}
+
+/// What git handed the hook: its arguments and, for some hooks, stdin
+#[derive(Debug, Clone, Default)]
+pub struct HookInput {
+    pub args: Vec<String>,
+    pub stdin: String,
 }
 
 /// Build context for an event
@@ -43,33 +100,108 @@ pub fn build_context(
     event: &str,
     level: ContextLevel,
     config: &Config,
+    input: &HookInput,
 ) -> Result<Context> {
     let repo = git2::Repository::open(repo_path)?;
 
-    let head = repo.head()?;
-    let commit = head.peel_to_commit()?;
+    // HEAD has no commit yet before the first one, which only staged events
+    // (pre-commit, prepare-commit-msg) can run into
+    let staged_event = is_staged_event(event);
+    // A tag event is about the tagged commit, wherever HEAD is
+    let target = match (event, input.args.first()) {
+        ("tag", Some(tag)) => repo
+            .find_reference(&format!("refs/tags/{}", tag))
+            .and_then(|r| r.peel_to_commit()),
+        _ => repo.head().and_then(|h| h.peel_to_commit()),
+    };
+    let commit = match target {
+        Ok(commit) => Some(commit),
+        Err(e) if staged_event && e.code() == git2::ErrorCode::UnbornBranch => None,
+        Err(e) => return Err(e.into()),
+    };
 
-    let branch = head
-        .shorthand()
-        .unwrap_or("HEAD")
-        .to_string();
+    let branch = current_branch(&repo);
 
-    let commit_hash = commit.id().to_string()[..7].to_string();
-    let commit_message = commit.message().unwrap_or("").to_string();
-    let author = commit.author().name().unwrap_or("Unknown").to_string();
+    let commit_hash = commit.as_ref().map(|c| c.id().to_string()[..7].to_string()).unwrap_or_default();
+    let commit_message = commit.as_ref().and_then(|c| c.message()).unwrap_or("").to_string();
+    let author = commit
+        .as_ref()
+        .map(|c| c.author().name().unwrap_or("Unknown").to_string())
+        .unwrap_or_default();
+    let author_email = commit
+        .as_ref()
+        .map(|c| c.author().email().unwrap_or("").to_string())
+        .unwrap_or_default();
 
-    let date = chrono::DateTime::from_timestamp(commit.time().seconds(), 0)
+    let date = commit
+        .as_ref()
+        .and_then(|c| chrono::DateTime::from_timestamp(c.time().seconds(), 0))
         .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
         .unwrap_or_default();
 
-    // Get diff
-    let (diff, diff_stat) = match level {
-        ContextLevel::Minimal => (String::new(), String::new()),
-        _ => super::diff::get_commit_diff(&repo, &commit, config)?,
+    // Staged events describe the commit being made, the others HEAD
+    let (mut diff, mut diff_stat, mut lines_changed, mut affected_files, staged_diff, staged_count) =
+        match commit.as_ref().filter(|_| !staged_event) {
+            Some(commit) => {
+                let (diff, diff_stat) = match level {
+                    ContextLevel::Minimal => (String::new(), String::new()),
+                    _ => super::diff::get_commit_diff(&repo, commit, config)?,
+                };
+                let lines_changed = super::diff::count_changed_lines(&repo, commit)?;
+                let affected_files = super::diff::get_affected_files(&repo, commit)?;
+                (diff, diff_stat, lines_changed, affected_files, None, None)
+            }
+            None => {
+                let staged = super::diff::get_staged_changes(&repo, config)?;
+                let count = staged.files.len();
+                (
+                    staged.diff.clone(),
+                    staged.diff_stat,
+                    staged.lines_changed,
+                    staged.files,
+                    Some(staged.diff),
+                    Some(count),
+                )
+            }
+        };
+
+    // A merge is described by everything it brought in, not just HEAD
+    let merge = match event {
+        "post-merge" => Some(super::merge::merge_info(&repo, input)?),
+        _ => None,
     };
+    if let Some(merge) = &merge {
+        if let Some(changes) = super::merge::merge_changes(&repo, merge, config)? {
+            if !matches!(level, ContextLevel::Minimal) {
+                diff = changes.diff;
+            }
+            diff_stat = changes.diff_stat;
+            lines_changed = changes.lines_changed;
+            affected_files = changes.files;
+        }
+    }
 
-    // Get affected files
-    let affected_files = super::diff::get_affected_files(&repo, &commit)?;
+    // A release is described by everything since the previous one
+    let release = match (event, input.args.first()) {
+        ("tag", Some(tag)) => Some(super::release::release_info(&repo, tag)?),
+        _ => None,
+    };
+    if let Some((info, _)) = &release {
+        let previous = info
+            .previous_tag
+            .as_deref()
+            .and_then(|t| repo.revparse_single(&format!("refs/tags/{}", t)).ok())
+            .and_then(|o| o.peel_to_commit().ok());
+        if let (Some(previous), Some(commit)) = (previous, &commit) {
+            let changes = super::diff::get_range_changes(&repo, &previous, commit, config)?;
+            if !matches!(level, ContextLevel::Minimal) {
+                diff = changes.diff;
+            }
+            diff_stat = changes.diff_stat;
+            lines_changed = changes.lines_changed;
+            affected_files = changes.files;
+        }
+    }
 
     // Get affected packages (monorepo)
     let affected_packages = if config.monorepo.enabled {
@@ -80,28 +212,75 @@ pub fn build_context(
 
     // Get recent commits for extended context
     let recent_commits = match level {
-        ContextLevel::Extended | ContextLevel::Full => {
+        ContextLevel::Extended | ContextLevel::Full if commit.is_some() => {
             get_recent_commits(&repo, config.context.smart.include_recent_commits)?
         }
         _ => vec![],
     };
 
+    let diff_config = (!matches!(level, ContextLevel::Minimal)).then_some(config);
+    let mut checkout = None;
+    let mut commit_count = None;
+    let (release, release_commits) = release.unzip();
+    let commits = match (event, &merge) {
+        ("pre-push", _) => super::push::pushed_commits(&repo, input, diff_config)?,
+        ("post-checkout", _) => {
+            let (info, commits) = super::checkout::checkout_info(&repo, repo_path, config, diff_config)?;
+            checkout = Some(info);
+            commits
+        }
+        ("post-rewrite", _) => super::rewrite::rewritten_commits(&repo, input, diff_config)?,
+        ("tag", _) => release_commits.unwrap_or_default(),
+        (_, Some(merge)) => {
+            let (commits, total) = super::merge::merged_commits(&repo, merge, diff_config)?;
+            commit_count = Some(total);
+            commits
+        }
+        _ => vec![],
+    };
+    let commit_count = commit_count.unwrap_or(commits.len());
+
     Ok(Context {
         commit_hash,
         commit_message,
         author,
+        author_email,
         date,
         branch,
         diff,
         diff_stat,
-        staged_diff: None,
-        staged_count: None,
+        lines_changed,
+        staged_diff,
+        staged_count,
         affected_files,
         affected_packages,
         recent_commits,
+        commits,
+        commit_count,
+        merge,
+        checkout,
+        release,
     })
 }
 
+/// Events about the changes staged for the next commit
+fn is_staged_event(event: &str) -> bool {
+    matches!(event, "pre-commit" | "prepare-commit-msg")
+}
+
+/// Short name of the checked out branch, even before its first commit
+pub(super) fn current_branch(repo: &git2::Repository) -> String {
+    if let Ok(head) = repo.head() {
+        return head.shorthand().unwrap_or("HEAD").to_string();
+    }
+
+    repo.find_reference("HEAD")
+        .ok()
+        .and_then(|r| r.symbolic_target().map(str::to_string))
+        .and_then(|target| target.strip_prefix("refs/heads/").map(str::to_string))
+        .unwrap_or_else(|| "HEAD".to_string())
+}
+
 fn get_recent_commits(repo: &git2::Repository, count: usize) -> Result<Vec<CommitInfo>> {
     let mut revwalk = repo.revwalk()?;
     revwalk.push_head()?;
@@ -111,14 +290,7 @@ fn get_recent_commits(repo: &git2::Repository, count: usize) -> Result<Vec<Commi
         .skip(1)
         .filter_map(|oid| oid.ok())
         .filter_map(|oid| repo.find_commit(oid).ok())
-        .map(|commit| CommitInfo {
-            hash: commit.id().to_string()[..7].to_string(),
-            message: commit.message().unwrap_or("").lines().next().unwrap_or("").to_string(),
-            author: commit.author().name().unwrap_or("Unknown").to_string(),
-            date: chrono::DateTime::from_timestamp(commit.time().seconds(), 0)
-                .map(|dt| dt.format("%Y-%m-%d").to_string())
-                .unwrap_or_default(),
-        })
+        .filter_map(|commit| CommitInfo::from_commit(repo, &commit, None).ok())
         .collect();
 
     Ok(commits)
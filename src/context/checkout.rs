@@ -0,0 +1,216 @@
+use anyhow::Result;
+use git2::{Oid, Repository, Sort};
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::builder::{current_branch, CommitInfo, HookInput};
+use crate::config::Config;
+
+/// Most branch commits listed in a briefing; older ones are dropped
+const MAX_BRANCH_COMMITS: usize = 50;
+
+/// Most TODO/FIXME lines listed
+const MAX_TODOS: usize = 20;
+
+/// Most earlier reviews included, newest first
+const MAX_REVIEWS: usize = 3;
+
+/// Lines kept of each earlier review
+const MAX_REVIEW_LINES: usize = 30;
+
+/// Briefing for the branch just checked out
+#[derive(Debug, Clone, Default)]
+pub struct CheckoutInfo {
+    /// What the branch's commits are compared against, e.g. `origin/main`
+    pub base_branch: Option<String>,
+    /// Commit authors on the branch, most commits first
+    pub authors: Vec<(String, usize)>,
+    /// `path:line: text` of TODO/FIXME lines the branch added
+    pub todos: Vec<String>,
+    /// Earlier gitclaude reviews of the branch's commits
+    pub reviews: Vec<Review>,
+}
+
+/// A gitclaude response saved for a commit
+#[derive(Debug, Clone)]
+pub struct Review {
+    pub hash: String,
+    pub content: String,
+}
+
+/// Why a post-checkout is not a branch switch worth a briefing: a file
+/// checkout (third argument `0`), the checkout at the end of a clone, or
+/// one of the checkouts a rebase makes
+pub fn checkout_skip_reason(repo: &Repository, input: &HookInput) -> Option<&'static str> {
+    if input.args.get(2).is_some_and(|flag| flag == "0") {
+        return Some("file checkout");
+    }
+    if input.args.first().is_some_and(|prev| !prev.is_empty() && prev.chars().all(|c| c == '0')) {
+        return Some("initial checkout after clone");
+    }
+    if repo.state() != git2::RepositoryState::Clean {
+        return Some("rebase or other operation in progress");
+    }
+    None
+}
+
+/// Collect the briefing for HEAD's branch along with the commits it has
+/// that its base does not, oldest first
+pub fn checkout_info(
+    repo: &Repository,
+    repo_root: &Path,
+    config: &Config,
+    diff_config: Option<&Config>,
+) -> Result<(CheckoutInfo, Vec<CommitInfo>)> {
+    let head = repo.head()?.peel_to_commit()?;
+    let Some((base_branch, base)) = base_for(repo, &current_branch(repo)) else {
+        return Ok((CheckoutInfo::default(), vec![]));
+    };
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+    revwalk.push(head.id())?;
+    revwalk.hide(base)?;
+    let mut oids: Vec<Oid> = revwalk.filter_map(|oid| oid.ok()).collect();
+
+    if oids.len() > MAX_BRANCH_COMMITS {
+        tracing::warn!("Branch has {} commits, listing the last {}", oids.len(), MAX_BRANCH_COMMITS);
+        oids.drain(..oids.len() - MAX_BRANCH_COMMITS);
+    }
+
+    let commits = oids
+        .iter()
+        .map(|oid| repo.find_commit(*oid))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for commit in &commits {
+        *counts.entry(commit.author().name().unwrap_or("Unknown").to_string()).or_default() += 1;
+    }
+    let mut authors: Vec<(String, usize)> = counts.into_iter().collect();
+    authors.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    let todos = match repo.merge_base(base, head.id()) {
+        Ok(fork_point) if !commits.is_empty() => added_todos(repo, fork_point, &head)?,
+        _ => vec![],
+    };
+
+    let reviews = earlier_reviews(repo, repo_root, config, &oids);
+
+    let commits = commits
+        .iter()
+        .map(|commit| CommitInfo::from_commit(repo, commit, diff_config))
+        .collect::<Result<Vec<_>>>()?;
+
+    let info = CheckoutInfo {
+        base_branch: Some(base_branch),
+        authors,
+        todos,
+        reviews,
+    };
+    Ok((info, commits))
+}
+
+/// The branch a briefing compares against: the default branch
+/// (`origin/HEAD`, else a local main or master), or the upstream when
+/// `branch` is the default branch itself
+fn base_for(repo: &Repository, branch: &str) -> Option<(String, Oid)> {
+    let default = repo
+        .find_reference("refs/remotes/origin/HEAD")
+        .ok()
+        .and_then(|r| r.symbolic_target().map(str::to_string))
+        .and_then(|target| target.strip_prefix("refs/remotes/").map(str::to_string))
+        .or_else(|| {
+            ["main", "master"]
+                .into_iter()
+                .find(|name| repo.find_branch(name, git2::BranchType::Local).is_ok())
+                .map(str::to_string)
+        });
+
+    let is_default = default
+        .as_deref()
+        .is_some_and(|d| d == branch || d.split_once('/').is_some_and(|(_, name)| name == branch));
+
+    let base = match default {
+        Some(default) if !is_default => default,
+        _ => {
+            let local = repo.find_branch(branch, git2::BranchType::Local).ok()?;
+            local.upstream().ok()?.name().ok()??.to_string()
+        }
+    };
+
+    let oid = repo.revparse_single(&base).ok()?.peel_to_commit().ok()?.id();
+    Some((base, oid))
+}
+
+/// TODO/FIXME lines added between `fork_point` and `head`
+fn added_todos(repo: &Repository, fork_point: Oid, head: &git2::Commit) -> Result<Vec<String>> {
+    let marker = Regex::new(r"\b(TODO|FIXME)\b").expect("valid regex");
+    let old_tree = repo.find_commit(fork_point)?.tree()?;
+    let diff = repo.diff_tree_to_tree(Some(&old_tree), Some(&head.tree()?), None)?;
+
+    let mut todos = vec![];
+    diff.foreach(
+        &mut |_, _| true,
+        None,
+        None,
+        Some(&mut |delta, _hunk, line| {
+            if todos.len() >= MAX_TODOS || line.origin() != '+' {
+                return true;
+            }
+            let Ok(text) = std::str::from_utf8(line.content()) else { return true };
+            if marker.is_match(text) {
+                let path = delta.new_file().path().map(|p| p.to_string_lossy().to_string()).unwrap_or_default();
+                todos.push(format!("{}:{}: {}", path, line.new_lineno().unwrap_or(0), text.trim()));
+            }
+            true
+        }),
+    )?;
+
+    Ok(todos)
+}
+
+/// The newest reviews of `oids`, from git notes or saved response files
+fn earlier_reviews(repo: &Repository, repo_root: &Path, config: &Config, oids: &[Oid]) -> Vec<Review> {
+    let mut reviews = vec![];
+
+    for oid in oids.iter().rev() {
+        if reviews.len() >= MAX_REVIEWS {
+            break;
+        }
+
+        let hash = oid.to_string()[..7].to_string();
+        let note = repo
+            .find_note(Some(&config.output.git_note.note_ref), *oid)
+            .ok()
+            .and_then(|n| n.message().map(str::to_string));
+        let content = note.or_else(|| {
+            let path = crate::output::find_responses(repo_root, &config.output.file, &hash)
+                .ok()?
+                .into_iter()
+                .next()?;
+            read_response(&path)
+        });
+
+        if let Some(content) = content {
+            let content: Vec<&str> = content.trim().lines().take(MAX_REVIEW_LINES).collect();
+            reviews.push(Review {
+                hash,
+                content: content.join("\n"),
+            });
+        }
+    }
+
+    reviews
+}
+
+/// Text of a saved response, unwrapping the JSON format
+fn read_response(path: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(path).ok()?;
+    if path.extension().is_some_and(|e| e == "json") {
+        let value: serde_json::Value = serde_json::from_str(&content).ok()?;
+        return value["response"].as_str().map(str::to_string);
+    }
+    Some(content)
+}
@@ -0,0 +1,77 @@
+use glob::{MatchOptions, Pattern};
+use regex::Regex;
+
+use super::builder::Context;
+use crate::config::WhenConfig;
+
+/// Check an event's `when` block against the context, returning the first
+/// condition that does not hold.
+///
+/// Invalid patterns are reported by `gitclaude config validate`; here they
+/// simply never match.
+pub fn unmet_condition(when: &WhenConfig, context: &Context) -> Option<String> {
+    if !when.branches.is_empty() && !any_glob(&when.branches, &context.branch, true) {
+        return Some(format!(
+            "branch '{}' does not match {}",
+            context.branch,
+            when.branches.join(", ")
+        ));
+    }
+
+    if !when.paths.is_empty()
+        && !context
+            .affected_files
+            .iter()
+            .any(|file| any_glob(&when.paths, file, true))
+    {
+        return Some(format!("no changed file matches {}", when.paths.join(", ")));
+    }
+
+    if !when.authors.is_empty()
+        && !any_glob(&when.authors, &context.author, false)
+        && !any_glob(&when.authors, &context.author_email, false)
+    {
+        return Some(format!(
+            "author '{}' does not match {}",
+            context.author,
+            when.authors.join(", ")
+        ));
+    }
+
+    if let Some(min) = when.min_lines {
+        if context.lines_changed < min {
+            return Some(format!(
+                "{} changed lines, fewer than min_lines = {}",
+                context.lines_changed, min
+            ));
+        }
+    }
+
+    if !when.message.is_empty()
+        && !when
+            .message
+            .iter()
+            .filter_map(|p| Regex::new(p).ok())
+            .any(|re| re.is_match(&context.commit_message))
+    {
+        return Some(format!(
+            "commit message does not match {}",
+            when.message.join(", ")
+        ));
+    }
+
+    None
+}
+
+fn any_glob(patterns: &[String], value: &str, case_sensitive: bool) -> bool {
+    let options = MatchOptions {
+        case_sensitive,
+        require_literal_separator: false,
+        require_literal_leading_dot: false,
+    };
+
+    patterns
+        .iter()
+        .filter_map(|p| Pattern::new(p).ok())
+        .any(|p| p.matches_with(value, options))
+}
@@ -1,5 +1,6 @@
 use anyhow::Result;
 use git2::{Commit, DiffOptions, Repository};
+use glob::Pattern;
 
 use crate::config::Config;
 
@@ -22,6 +23,85 @@ pub fn get_commit_diff(
         Some(&mut opts),
     )?;
 
+    render_diff(&diff, config)
+}
+
+/// Patch, stat and touched files of a set of changes
+#[derive(Debug, Clone, Default)]
+pub struct Changes {
+    pub diff: String,
+    pub diff_stat: String,
+    /// Every changed path, excluded ones included
+    pub files: Vec<String>,
+    pub lines_changed: usize,
+}
+
+/// Diff the index against HEAD, like `git diff --cached`.
+///
+/// Before the first commit everything staged is new. While a hook runs for
+/// `git commit -a`, `--only` or `<paths>`, git points `GIT_INDEX_FILE` at
+/// the temporary index it is about to commit, so that index is used
+/// instead of the repository's.
+pub fn get_staged_changes(repo: &Repository, config: &Config) -> Result<Changes> {
+    let head_tree = repo.head().ok().and_then(|h| h.peel_to_tree().ok());
+    let index = staged_index(repo)?;
+
+    let mut opts = DiffOptions::new();
+    opts.context_lines(3);
+
+    let diff = repo.diff_tree_to_index(head_tree.as_ref(), Some(&index), Some(&mut opts))?;
+    summarize(&diff, config)
+}
+
+/// Changes between two commits, like `git diff <from> <to>`
+pub fn get_range_changes(repo: &Repository, from: &Commit, to: &Commit, config: &Config) -> Result<Changes> {
+    let mut opts = DiffOptions::new();
+    opts.context_lines(3);
+
+    let diff = repo.diff_tree_to_tree(Some(&from.tree()?), Some(&to.tree()?), Some(&mut opts))?;
+    summarize(&diff, config)
+}
+
+fn summarize(diff: &git2::Diff, config: &Config) -> Result<Changes> {
+    let (diff_content, diff_stat) = render_diff(diff, config)?;
+    let stats = diff.stats()?;
+
+    let files = diff
+        .deltas()
+        .filter_map(|d| d.new_file().path().or_else(|| d.old_file().path()))
+        .map(|p| p.to_string_lossy().to_string())
+        .collect();
+
+    Ok(Changes {
+        diff: diff_content,
+        diff_stat,
+        files,
+        lines_changed: stats.insertions() + stats.deletions(),
+    })
+}
+
+/// The index the commit in progress is made from
+fn staged_index(repo: &Repository) -> Result<git2::Index> {
+    let Some(path) = std::env::var_os("GIT_INDEX_FILE") else {
+        return Ok(repo.index()?);
+    };
+
+    // Relative paths are relative to the worktree hooks run in
+    let path = std::path::PathBuf::from(path);
+    let path = match repo.workdir() {
+        Some(workdir) if path.is_relative() => workdir.join(path),
+        _ => path,
+    };
+
+    Ok(git2::Index::open(&path)?)
+}
+
+/// Patch text and stat line of a diff.
+///
+/// Files matching `context.exclude.patterns` are left out of the patch
+/// (but still counted in the stat), and the patch is cut at
+/// `context.smart.truncate_at` lines.
+fn render_diff(diff: &git2::Diff, config: &Config) -> Result<(String, String)> {
     // Generate diff stat
     let stats = diff.stats()?;
     let diff_stat = format!(
@@ -35,8 +115,26 @@ pub fn get_commit_diff(
     let mut diff_content = String::new();
     let mut current_lines = 0;
     let max_lines = config.context.smart.truncate_at;
+    let excludes: Vec<Pattern> = config
+        .context
+        .exclude
+        .patterns
+        .iter()
+        .filter_map(|p| Pattern::new(p).ok())
+        .collect();
+    let mut excluded: Vec<String> = vec![];
+
+    diff.print(git2::DiffFormat::Patch, |delta, _hunk, line| {
+        if let Some(path) = delta.new_file().path().or_else(|| delta.old_file().path()) {
+            if excludes.iter().any(|p| p.matches_path(path)) {
+                let path = path.to_string_lossy().to_string();
+                if !excluded.contains(&path) {
+                    excluded.push(path);
+                }
+                return true;
+            }
+        }
 
-    diff.print(git2::DiffFormat::Patch, |_delta, _hunk, line| {
         if current_lines >= max_lines {
             return true;
         }
@@ -62,10 +160,25 @@ pub fn get_commit_diff(
     if current_lines >= max_lines {
         diff_content.push_str("\n... [truncated] ...\n");
     }
+    if !excluded.is_empty() {
+        diff_content.push_str(&format!("\n... [excluded: {}] ...\n", excluded.join(", ")));
+    }
 
     Ok((diff_content, diff_stat))
 }
 
+/// Count inserted plus deleted lines in a commit
+pub fn count_changed_lines(repo: &Repository, commit: &Commit) -> Result<usize> {
+    let parent = commit.parent(0).ok();
+    let parent_tree = parent.as_ref().and_then(|p| p.tree().ok());
+    let commit_tree = commit.tree()?;
+
+    let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit_tree), None)?;
+    let stats = diff.stats()?;
+
+    Ok(stats.insertions() + stats.deletions())
+}
+
 /// Get list of affected files in a commit
 pub fn get_affected_files(repo: &Repository, commit: &Commit) -> Result<Vec<String>> {
     let parent = commit.parent(0).ok();
@@ -0,0 +1,190 @@
+use anyhow::Result;
+use git2::{Oid, Repository, Sort};
+
+use super::builder::{current_branch, CommitInfo, HookInput};
+use super::diff::Changes;
+use crate::config::Config;
+
+/// Most commits listed for a single merge; older ones are dropped
+const MAX_MERGED_COMMITS: usize = 50;
+
+/// What a `git merge` or `git pull` just did
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeInfo {
+    /// Branch (or branches, for an octopus merge) that was merged
+    pub source_branch: String,
+    /// Branch merged into
+    pub target_branch: String,
+    /// `git merge --squash`: changes are staged, HEAD did not move
+    pub squash: bool,
+    /// HEAD moved to the merged branch without a merge commit
+    pub fast_forward: bool,
+    /// HEAD before the merge (`ORIG_HEAD`)
+    pub orig_head: Option<Oid>,
+}
+
+/// Work out the merge post-merge runs for.
+///
+/// The squash flag is the hook's argument. The merged branch comes from the
+/// command git records as `GIT_REFLOG_ACTION` (`merge feat`,
+/// `pull origin main`), or the HEAD reflog when run by hand, falling back to
+/// a branch pointing at `MERGE_HEAD`.
+pub fn merge_info(repo: &Repository, input: &HookInput) -> Result<MergeInfo> {
+    let squash = input.args.first().is_some_and(|a| a == "1");
+    let target_branch = current_branch(repo);
+
+    let orig_head = repo.refname_to_id("ORIG_HEAD").ok();
+    let head = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+    let fast_forward = !squash
+        && orig_head.is_some_and(|orig| Some(orig) != head.as_ref().map(|h| h.id()))
+        && head.as_ref().is_some_and(|h| h.parent_count() < 2);
+
+    let source_branch = merge_action(repo)
+        .and_then(|action| branch_from_action(repo, &action, &target_branch))
+        .or_else(|| branch_at(repo, "MERGE_HEAD"))
+        .unwrap_or_else(|| "unknown".to_string());
+
+    Ok(MergeInfo {
+        source_branch,
+        target_branch,
+        squash,
+        fast_forward,
+        orig_head,
+    })
+}
+
+/// Commits the merge brought in, oldest first, without the merge commit,
+/// and how many there were before the list was capped.
+///
+/// A squash merge lists the commits git wrote to `SQUASH_MSG`; otherwise
+/// these are the commits reachable from HEAD but not from `ORIG_HEAD`.
+pub fn merged_commits(
+    repo: &Repository,
+    merge: &MergeInfo,
+    diff_config: Option<&Config>,
+) -> Result<(Vec<CommitInfo>, usize)> {
+    let mut oids = if merge.squash {
+        squashed_commits(repo)
+    } else {
+        let Some(orig_head) = merge.orig_head else {
+            return Ok((vec![], 0));
+        };
+        let head = repo.head()?.peel_to_commit()?;
+
+        let mut revwalk = repo.revwalk()?;
+        revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+        revwalk.push(head.id())?;
+        revwalk.hide(orig_head)?;
+
+        let merge_commit = (head.parent_count() > 1).then(|| head.id());
+        revwalk
+            .filter_map(|oid| oid.ok())
+            .filter(|oid| Some(*oid) != merge_commit)
+            .collect()
+    };
+
+    let total = oids.len();
+    if total > MAX_MERGED_COMMITS {
+        tracing::warn!("Merge brought in {} commits, listing the last {}", total, MAX_MERGED_COMMITS);
+        oids.drain(..total - MAX_MERGED_COMMITS);
+    }
+
+    let commits = oids
+        .iter()
+        .map(|oid| CommitInfo::from_commit(repo, &repo.find_commit(*oid)?, diff_config))
+        .collect::<Result<_>>()?;
+    Ok((commits, total))
+}
+
+/// The combined changes of the merge: what is staged for a squash, and
+/// `ORIG_HEAD..HEAD` otherwise. `None` when there is nothing to compare.
+pub fn merge_changes(repo: &Repository, merge: &MergeInfo, config: &Config) -> Result<Option<Changes>> {
+    if merge.squash {
+        return Ok(Some(super::diff::get_staged_changes(repo, config)?));
+    }
+
+    let Some(orig_head) = merge.orig_head.and_then(|oid| repo.find_commit(oid).ok()) else {
+        return Ok(None);
+    };
+    let head = repo.head()?.peel_to_commit()?;
+
+    Ok(Some(super::diff::get_range_changes(repo, &orig_head, &head, config)?))
+}
+
+/// The command behind the merge, e.g. `merge feat` or `pull origin main`
+fn merge_action(repo: &Repository) -> Option<String> {
+    if let Ok(action) = std::env::var("GIT_REFLOG_ACTION") {
+        if !action.is_empty() {
+            return Some(action);
+        }
+    }
+
+    let reflog = repo.reflog("HEAD").ok()?;
+    let message = reflog.get(0)?.message()?.to_string();
+    message.split_once(':').map(|(action, _)| action.to_string())
+}
+
+/// Name the merged branch from a `merge ...` or `pull ...` command line.
+///
+/// Only arguments that resolve to a commit count, which skips option values
+/// like the message of `-m`. A bare `git pull` merges the upstream.
+fn branch_from_action(repo: &Repository, action: &str, target: &str) -> Option<String> {
+    let mut words = action.split_whitespace();
+    let command = words.next()?;
+    let args: Vec<&str> = words.filter(|w| !w.starts_with('-')).collect();
+
+    match command {
+        "merge" => {
+            let branches: Vec<&str> = args
+                .into_iter()
+                .filter(|arg| repo.revparse_single(arg).is_ok())
+                .collect();
+            (!branches.is_empty()).then(|| branches.join(", "))
+        }
+        "pull" => match args.as_slice() {
+            [] => upstream(repo, target),
+            [remote] => upstream(repo, target)
+                .filter(|u| u.starts_with(&format!("{}/", remote)))
+                .or_else(|| Some(remote.to_string())),
+            [remote, refs @ ..] => Some(
+                refs.iter()
+                    .map(|r| format!("{}/{}", remote, r))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ),
+        },
+        _ => None,
+    }
+}
+
+/// Upstream of a local branch, e.g. `origin/main`
+fn upstream(repo: &Repository, branch: &str) -> Option<String> {
+    let branch = repo.find_branch(branch, git2::BranchType::Local).ok()?;
+    let upstream = branch.upstream().ok()?;
+    upstream.name().ok()?.map(str::to_string)
+}
+
+/// A local or remote-tracking branch whose tip is the commit `name` points to
+fn branch_at(repo: &Repository, name: &str) -> Option<String> {
+    let target = repo.refname_to_id(name).ok()?;
+
+    repo.branches(None)
+        .ok()?
+        .filter_map(|b| b.ok())
+        .find(|(b, _)| b.get().target() == Some(target))
+        .and_then(|(b, _)| b.name().ok().flatten().map(str::to_string))
+}
+
+/// Commits listed in `SQUASH_MSG`, oldest first (git writes newest first)
+fn squashed_commits(repo: &Repository) -> Vec<Oid> {
+    let message = std::fs::read_to_string(repo.path().join("SQUASH_MSG")).unwrap_or_default();
+
+    let mut oids: Vec<Oid> = message
+        .lines()
+        .filter_map(|line| line.strip_prefix("commit "))
+        .filter_map(|sha| Oid::from_str(sha.trim()).ok())
+        .filter(|oid| repo.find_commit(*oid).is_ok())
+        .collect();
+    oids.reverse();
+    oids
+}
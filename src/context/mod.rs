@@ -1,9 +1,20 @@
 //! Context building for Claude prompts
 
 mod builder;
+mod checkout;
+mod conditions;
 mod diff;
+mod merge;
 mod monorepo;
+mod push;
+mod release;
+mod rewrite;
 
 pub use builder::*;
+pub use checkout::*;
+pub use conditions::*;
 pub use diff::*;
 pub use monorepo::*;
+pub use push::*;
+pub use release::*;
+pub use rewrite::*;
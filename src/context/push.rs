@@ -0,0 +1,203 @@
+use anyhow::Result;
+use git2::{Oid, Repository, Sort};
+use std::collections::HashSet;
+
+use super::builder::{CommitInfo, HookInput};
+use crate::config::Config;
+
+/// Most commits listed for a single push; older ones are dropped
+const MAX_PUSHED_COMMITS: usize = 50;
+
+/// One `<local ref> <local sha> <remote ref> <remote sha>` line git passes
+/// to pre-push on stdin
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PushUpdate {
+    pub local_ref: String,
+    pub local_sha: String,
+    pub remote_ref: String,
+    pub remote_sha: String,
+}
+
+impl PushUpdate {
+    /// The push deletes the remote ref
+    pub fn is_delete(&self) -> bool {
+        is_zero(&self.local_sha)
+    }
+
+    /// The remote ref does not exist yet
+    pub fn is_new(&self) -> bool {
+        is_zero(&self.remote_sha)
+    }
+}
+
+/// Parse pre-push stdin, skipping malformed lines
+pub fn parse_push_updates(input: &str) -> Vec<PushUpdate> {
+    input
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let update = PushUpdate {
+                local_ref: fields.next()?.to_string(),
+                local_sha: fields.next()?.to_string(),
+                remote_ref: fields.next()?.to_string(),
+                remote_sha: fields.next()?.to_string(),
+            };
+            fields.next().is_none().then_some(update)
+        })
+        .collect()
+}
+
+/// The pre-push input as the pre-commit framework passes it on.
+///
+/// pre-commit reads git's stdin itself and runs each hook once per pushed
+/// ref, describing it in `PRE_COMMIT_*` variables instead. `None` outside
+/// pre-commit, or for a push it skips (deletions).
+pub fn pre_commit_push_input() -> Option<HookInput> {
+    let var = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+
+    let local_sha = var("PRE_COMMIT_TO_REF")?;
+    let update = [
+        var("PRE_COMMIT_LOCAL_BRANCH").unwrap_or_else(|| local_sha.clone()),
+        local_sha,
+        var("PRE_COMMIT_REMOTE_BRANCH").unwrap_or_default(),
+        // Missing for a new ref, which pre-commit then checks in full
+        var("PRE_COMMIT_FROM_REF").unwrap_or_else(|| Oid::zero().to_string()),
+    ];
+
+    Some(HookInput {
+        args: [var("PRE_COMMIT_REMOTE_NAME"), var("PRE_COMMIT_REMOTE_URL")]
+            .into_iter()
+            .flatten()
+            .collect(),
+        stdin: update.join(" ") + "\n",
+    })
+}
+
+/// Updates for the push described by a hook's input.
+///
+/// Without stdin (a manual `gitclaude run pre-push`) this is the current
+/// branch pushed to its upstream.
+pub fn push_updates(repo: &Repository, input: &HookInput) -> Vec<PushUpdate> {
+    if !input.stdin.trim().is_empty() {
+        return parse_push_updates(&input.stdin);
+    }
+
+    upstream_update(repo).into_iter().collect()
+}
+
+/// Commits a push sends that the remote does not have yet, oldest first.
+///
+/// Commits reachable from several pushed refs are listed once. Diffs are
+/// included when `diff_config` is given.
+pub fn pushed_commits(
+    repo: &Repository,
+    input: &HookInput,
+    diff_config: Option<&Config>,
+) -> Result<Vec<CommitInfo>> {
+    let remote = input.args.first().map(String::as_str);
+    let mut seen = HashSet::new();
+    let mut oids = vec![];
+
+    for update in push_updates(repo, input) {
+        if update.is_delete() {
+            continue;
+        }
+
+        for oid in new_commits(repo, remote, &update)? {
+            if seen.insert(oid) {
+                oids.push(oid);
+            }
+        }
+    }
+
+    if oids.len() > MAX_PUSHED_COMMITS {
+        tracing::warn!("Push has {} new commits, listing the last {}", oids.len(), MAX_PUSHED_COMMITS);
+        oids.drain(..oids.len() - MAX_PUSHED_COMMITS);
+    }
+
+    oids.iter()
+        .map(|oid| CommitInfo::from_commit(repo, &repo.find_commit(*oid)?, diff_config))
+        .collect()
+}
+
+/// Whether the remote-tracking refs show the pushed commits, meaning the
+/// push went through. `None` when no pushed ref has a tracking ref to check
+/// (tags, or a push to a URL rather than a named remote).
+pub fn push_landed(repo: &Repository, input: &HookInput) -> Option<bool> {
+    let remote = input.args.first()?;
+    repo.find_remote(remote).ok()?;
+
+    let mut checked = false;
+    for update in push_updates(repo, input) {
+        let Some(branch) = update.remote_ref.strip_prefix("refs/heads/") else { continue };
+        let tracking = format!("refs/remotes/{}/{}", remote, branch);
+        let current = repo
+            .find_reference(&tracking)
+            .ok()
+            .and_then(|r| r.target())
+            .map(|oid| oid.to_string());
+
+        let expected = (!update.is_delete()).then(|| update.local_sha.clone());
+        if current != expected {
+            return Some(false);
+        }
+        checked = true;
+    }
+
+    checked.then_some(true)
+}
+
+/// Commits in `update` the remote does not have, like
+/// `git rev-list <local> ^<remote>` (or `--not --remotes=<remote>` for a new ref)
+fn new_commits(repo: &Repository, remote: Option<&str>, update: &PushUpdate) -> Result<Vec<Oid>> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+    revwalk.push(Oid::from_str(&update.local_sha)?)?;
+
+    let known_remote = Oid::from_str(&update.remote_sha)
+        .ok()
+        .filter(|_| !update.is_new())
+        .filter(|oid| repo.find_commit(*oid).is_ok());
+
+    match known_remote {
+        Some(oid) => revwalk.hide(oid)?,
+        // New ref, or a force push over commits we don't have: everything
+        // the remote is known to have is not new
+        None => {
+            let glob = match remote {
+                Some(name) if repo.find_remote(name).is_ok() => format!("refs/remotes/{}/*", name),
+                _ => "refs/remotes/*".to_string(),
+            };
+            revwalk.hide_glob(&glob)?;
+        }
+    }
+
+    Ok(revwalk.filter_map(|oid| oid.ok()).collect())
+}
+
+/// The current branch as it would be pushed to its upstream
+fn upstream_update(repo: &Repository) -> Option<PushUpdate> {
+    let head = repo.head().ok()?;
+    let local_sha = head.target()?.to_string();
+    let branch = git2::Branch::wrap(head);
+    let local_ref = branch.get().name()?.to_string();
+
+    let (remote_ref, remote_sha) = match branch.upstream() {
+        Ok(upstream) => (
+            upstream.get().name()?.to_string(),
+            upstream.get().target()?.to_string(),
+        ),
+        Err(_) => (local_ref.clone(), Oid::zero().to_string()),
+    };
+
+    Some(PushUpdate {
+        local_ref,
+        local_sha,
+        remote_ref,
+        remote_sha,
+    })
+}
+
+pub(super) fn is_zero(sha: &str) -> bool {
+    sha.chars().all(|c| c == '0')
+}
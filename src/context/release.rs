@@ -0,0 +1,337 @@
+use anyhow::{Context as _, Result};
+use git2::{Oid, Repository, Sort};
+use regex::Regex;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use super::builder::{CommitInfo, HookInput};
+use super::push::{is_zero, parse_push_updates};
+
+/// Most commits collected for one release; older ones are dropped
+const MAX_RELEASE_COMMITS: usize = 200;
+
+/// Commit groups in the order release notes list them, by conventional
+/// commit type
+const GROUPS: &[(&str, &[&str])] = &[
+    ("Breaking Changes", &[]),
+    ("Features", &["feat"]),
+    ("Fixes", &["fix"]),
+    ("Performance", &["perf"]),
+    ("Refactoring", &["refactor"]),
+    ("Documentation", &["docs"]),
+    ("Maintenance", &["chore", "ci", "build", "test", "style", "revert"]),
+    ("Other", &[]),
+];
+
+/// Git commands whose ref updates bring in tags made elsewhere
+const IMPORTING_COMMANDS: &[&str] = &["fetch", "pull", "clone", "remote", "receive-pack", "fast-import"];
+
+/// Options git takes before the subcommand whose value is a separate argument
+const GIT_OPTIONS_WITH_VALUE: &[&str] =
+    &["-C", "-c", "--git-dir", "--work-tree", "--namespace", "--super-prefix", "--config-env", "--attr-source"];
+
+/// A release cut by creating a tag
+#[derive(Debug, Clone)]
+pub struct ReleaseInfo {
+    pub tag: String,
+    /// Tag the release notes start from, `None` for the first release
+    pub previous_tag: Option<String>,
+    /// Commits since the previous tag by group, empty groups left out
+    pub groups: Vec<(String, Vec<CommitInfo>)>,
+}
+
+/// Tags a `reference-transaction` hook input creates.
+///
+/// Only the `committed` phase counts, and not when the transaction belongs
+/// to a fetch, clone or other command bringing in tags from elsewhere. When
+/// the command can't be told, the tags are assumed to be imported too.
+pub fn created_tags(input: &HookInput) -> Vec<String> {
+    if input.args.first().map(String::as_str) != Some("committed") {
+        return vec![];
+    }
+
+    let tags: Vec<String> = input
+        .stdin
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let (old, new, name) = (fields.next()?, fields.next()?, fields.next()?);
+            let tag = name.strip_prefix("refs/tags/")?;
+            (is_zero(old) && !is_zero(new)).then(|| tag.to_string())
+        })
+        .collect();
+
+    if tags.is_empty() || hook_git_command().is_none_or(|c| IMPORTING_COMMANDS.contains(&c.as_str())) {
+        return vec![];
+    }
+    tags
+}
+
+/// New tags in a `pre-push` hook input
+pub fn pushed_tags(input: &HookInput) -> Vec<String> {
+    parse_push_updates(&input.stdin)
+        .into_iter()
+        .filter(|u| u.is_new() && !u.is_delete())
+        .filter_map(|u| u.remote_ref.strip_prefix("refs/tags/").map(str::to_string))
+        .collect()
+}
+
+/// Collect the commits between `tag` and the tag before it and group them.
+///
+/// The previous tag is the highest semver tag below `tag` with the same
+/// prefix (`v`, `app-v`, ...), skipping pre-releases when `tag` is a final
+/// release. Tags that aren't semver fall back to the nearest older tag in
+/// history.
+pub fn release_info(repo: &Repository, tag: &str) -> Result<(ReleaseInfo, Vec<CommitInfo>)> {
+    let target = tag_commit(repo, tag).with_context(|| format!("Tag '{}' not found", tag))?;
+
+    let previous_tag = previous_semver_tag(repo, tag).or_else(|| nearest_older_tag(repo, target));
+    let previous = previous_tag.as_deref().and_then(|t| tag_commit(repo, t));
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+    revwalk.push(target)?;
+    if let Some(previous) = previous {
+        revwalk.hide(previous)?;
+    }
+    let mut oids: Vec<Oid> = revwalk.filter_map(|oid| oid.ok()).collect();
+
+    if oids.len() > MAX_RELEASE_COMMITS {
+        tracing::warn!("Release has {} commits, listing the last {}", oids.len(), MAX_RELEASE_COMMITS);
+        oids.drain(..oids.len() - MAX_RELEASE_COMMITS);
+    }
+
+    let mut commits = vec![];
+    let mut grouped: HashMap<&str, Vec<CommitInfo>> = HashMap::new();
+    for oid in &oids {
+        let commit = repo.find_commit(*oid)?;
+        let info = CommitInfo::from_commit(repo, &commit, None)?;
+        grouped
+            .entry(group_for(commit.message().unwrap_or("")))
+            .or_default()
+            .push(info.clone());
+        commits.push(info);
+    }
+
+    let groups = GROUPS
+        .iter()
+        .filter_map(|(title, _)| grouped.remove(title).map(|c| (title.to_string(), c)))
+        .collect();
+
+    let info = ReleaseInfo {
+        tag: tag.to_string(),
+        previous_tag,
+        groups,
+    };
+    Ok((info, commits))
+}
+
+/// Group of a commit by its conventional commit header, e.g. `feat(api)!:`
+fn group_for(message: &str) -> &'static str {
+    static HEADER: OnceLock<Regex> = OnceLock::new();
+    let header = HEADER.get_or_init(|| Regex::new(r"^(\w+)(\([^)]*\))?(!)?:").expect("valid regex"));
+    let subject = message.lines().next().unwrap_or("");
+
+    let Some(caps) = header.captures(subject) else {
+        return if message.contains("BREAKING CHANGE") { GROUPS[0].0 } else { "Other" };
+    };
+    if caps.get(3).is_some() || message.contains("BREAKING CHANGE") {
+        return GROUPS[0].0;
+    }
+
+    let kind = caps[1].to_lowercase();
+    GROUPS
+        .iter()
+        .find(|(_, kinds)| kinds.contains(&kind.as_str()))
+        .map(|(title, _)| *title)
+        .unwrap_or("Other")
+}
+
+/// Commit a tag points to, peeling annotated tags
+fn tag_commit(repo: &Repository, tag: &str) -> Option<Oid> {
+    let reference = repo.find_reference(&format!("refs/tags/{}", tag)).ok()?;
+    Some(reference.peel_to_commit().ok()?.id())
+}
+
+fn previous_semver_tag(repo: &Repository, tag: &str) -> Option<String> {
+    let (prefix, version) = Version::parse(tag)?;
+    let names = repo.tag_names(None).ok()?;
+
+    names
+        .iter()
+        .flatten()
+        .filter_map(|name| Version::parse(name).map(|(p, v)| (name, p, v)))
+        .filter(|(_, p, v)| *p == prefix && *v < version && (version.pre.is_some() || v.pre.is_none()))
+        .max_by(|a, b| a.2.cmp(&b.2))
+        .map(|(name, _, _)| name.to_string())
+}
+
+/// The first tagged commit in the history before `target`
+fn nearest_older_tag(repo: &Repository, target: Oid) -> Option<String> {
+    let mut tagged: HashMap<Oid, String> = HashMap::new();
+    for name in repo.tag_names(None).ok()?.iter().flatten() {
+        if let Some(oid) = tag_commit(repo, name) {
+            tagged.entry(oid).or_insert_with(|| name.to_string());
+        }
+    }
+
+    let mut revwalk = repo.revwalk().ok()?;
+    revwalk.set_sorting(Sort::TOPOLOGICAL).ok()?;
+    revwalk.push(target).ok()?;
+
+    revwalk
+        .filter_map(|oid| oid.ok())
+        .filter(|oid| *oid != target)
+        .find_map(|oid| tagged.get(&oid).cloned())
+}
+
+/// Subcommand of the git process running this hook, e.g. `fetch`.
+///
+/// Walks up the process tree from the hook script, so it is `None` where
+/// the tree can't be read or no git process is found (e.g. a tag created
+/// through libgit2).
+#[cfg(unix)]
+fn hook_git_command() -> Option<String> {
+    let mut pid = std::os::unix::process::parent_id();
+
+    for _ in 0..8 {
+        let (args, parent) = process_info(pid)?;
+
+        let program = args.first().map(|p| p.rsplit('/').next().unwrap_or(p).to_string());
+        if program.as_deref() == Some("git") {
+            return git_subcommand(&args[1..]);
+        }
+        if let Some(command) = program.as_deref().and_then(|p| p.strip_prefix("git-")) {
+            return Some(command.to_string());
+        }
+
+        if parent <= 1 {
+            return None;
+        }
+        pid = parent;
+    }
+
+    None
+}
+
+#[cfg(not(unix))]
+fn hook_git_command() -> Option<String> {
+    None
+}
+
+/// Arguments and parent pid of process `pid`, from `/proc` where there is
+/// one and from `ps` elsewhere (macOS). `ps` joins arguments with spaces,
+/// which still leaves git's subcommand intact.
+#[cfg(unix)]
+fn process_info(pid: u32) -> Option<(Vec<String>, u32)> {
+    if let Ok(cmdline) = std::fs::read(format!("/proc/{}/cmdline", pid)) {
+        let args = cmdline
+            .split(|b| *b == 0)
+            .filter(|a| !a.is_empty())
+            .map(|a| String::from_utf8_lossy(a).to_string())
+            .collect();
+
+        let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+        // `pid (comm) state ppid ...`, where comm may contain spaces
+        let parent = stat.rsplit_once(')')?.1.split_whitespace().nth(1)?.parse().ok()?;
+        return Some((args, parent));
+    }
+
+    let output = std::process::Command::new("ps")
+        .args(["-o", "ppid=", "-o", "args=", "-p", &pid.to_string()])
+        .output()
+        .ok()?;
+    let line = String::from_utf8_lossy(&output.stdout);
+    let mut fields = line.split_whitespace();
+    let parent = fields.next()?.parse().ok()?;
+
+    Some((fields.map(str::to_string).collect(), parent))
+}
+
+/// The subcommand in git's arguments, skipping options like `-C <path>`
+/// and `-c <name>=<value>` that come before it
+fn git_subcommand(args: &[String]) -> Option<String> {
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        if GIT_OPTIONS_WITH_VALUE.contains(&arg.as_str()) {
+            args.next();
+        } else if !arg.starts_with('-') {
+            return Some(arg.clone());
+        }
+    }
+
+    None
+}
+
+/// A semver version, ordered by semver precedence (build metadata ignored)
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Version {
+    major: u64,
+    minor: u64,
+    patch: u64,
+    pre: Option<String>,
+}
+
+impl Version {
+    /// Split a tag like `app-v1.2.0-rc.1` into its prefix and version
+    fn parse(tag: &str) -> Option<(String, Version)> {
+        static PATTERN: OnceLock<Regex> = OnceLock::new();
+        let pattern = PATTERN.get_or_init(|| {
+            Regex::new(r"^(.*?)(\d+)\.(\d+)\.(\d+)(?:-([0-9A-Za-z.-]+))?(?:\+[0-9A-Za-z.-]+)?$").expect("valid regex")
+        });
+        let caps = pattern.captures(tag)?;
+
+        let version = Version {
+            major: caps[2].parse().ok()?,
+            minor: caps[3].parse().ok()?,
+            patch: caps[4].parse().ok()?,
+            pre: caps.get(5).map(|m| m.as_str().to_string()),
+        };
+        Some((caps[1].to_string(), version))
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (&self.pre, &other.pre) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(a), Some(b)) => compare_pre(a, b),
+            })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Compare pre-release identifiers: numeric ones numerically and below
+/// alphanumeric ones, a shorter list first when all else is equal
+fn compare_pre(a: &str, b: &str) -> Ordering {
+    let mut left = a.split('.');
+    let mut right = b.split('.');
+
+    loop {
+        let ordering = match (left.next(), right.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) => match (x.parse::<u64>(), y.parse::<u64>()) {
+                (Ok(x), Ok(y)) => x.cmp(&y),
+                (Ok(_), Err(_)) => Ordering::Less,
+                (Err(_), Ok(_)) => Ordering::Greater,
+                (Err(_), Err(_)) => x.cmp(y),
+            },
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use anyhow::Result;
+use git2::{Commit, Oid, Repository};
+
+use super::builder::{CommitInfo, HookInput};
+use crate::config::Config;
+
+/// A commit replaced by `git commit --amend` or `git rebase`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rewrite {
+    pub old: Oid,
+    pub new: Oid,
+    /// Old and new commit have the same patch-id, i.e. the same changes
+    pub unchanged: bool,
+}
+
+/// Parse the `<old sha> <new sha> [<extra>]` lines git passes to
+/// post-rewrite and compare each pair's patch-id.
+///
+/// Pairs whose old commit is gone (e.g. already pruned) count as changed.
+pub fn parse_rewrites(repo: &Repository, input: &HookInput) -> Result<Vec<Rewrite>> {
+    let mut rewrites = vec![];
+
+    for line in input.stdin.lines() {
+        let mut fields = line.split_whitespace();
+        let (Some(old), Some(new)) = (fields.next(), fields.next()) else { continue };
+        let (Ok(old), Ok(new)) = (Oid::from_str(old), Oid::from_str(new)) else { continue };
+
+        let unchanged = match (repo.find_commit(old), repo.find_commit(new)) {
+            (Ok(old_commit), Ok(new_commit)) => patch_id(repo, &old_commit)? == patch_id(repo, &new_commit)?,
+            _ => false,
+        };
+
+        rewrites.push(Rewrite { old, new, unchanged });
+    }
+
+    Ok(rewrites)
+}
+
+/// New commits whose changes differ from the commit they replaced
+pub fn rewritten_commits(
+    repo: &Repository,
+    input: &HookInput,
+    diff_config: Option<&Config>,
+) -> Result<Vec<CommitInfo>> {
+    parse_rewrites(repo, input)?
+        .iter()
+        .filter(|r| !r.unchanged)
+        .map(|r| CommitInfo::from_commit(repo, &repo.find_commit(r.new)?, diff_config))
+        .collect()
+}
+
+/// Whether HEAD was just moved by an amend or a rebase, which post-rewrite
+/// reports on its own
+pub fn is_rewriting(repo: &Repository) -> bool {
+    if repo.state() != git2::RepositoryState::Clean {
+        return true;
+    }
+
+    let action = std::env::var("GIT_REFLOG_ACTION").unwrap_or_default();
+    if action.starts_with("rebase") {
+        return true;
+    }
+
+    repo.reflog("HEAD")
+        .ok()
+        .and_then(|log| log.get(0).and_then(|e| e.message().map(str::to_string)))
+        .is_some_and(|msg| msg.starts_with("commit (amend)") || msg.starts_with("rebase"))
+}
+
+/// Patch-id of a commit's changes against its first parent, stable across
+/// rebases that don't change the diff
+fn patch_id(repo: &Repository, commit: &Commit) -> Result<Oid> {
+    let parent_tree = commit.parent(0).ok().and_then(|p| p.tree().ok());
+    let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+    Ok(diff.patchid(None)?)
+}
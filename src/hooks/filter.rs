@@ -0,0 +1,118 @@
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+use crate::config::{self, expand_home, GlobalConfig};
+
+/// Marker in the repo's state dir recording that `gitclaude enable` ran.
+/// The state dir is shared by all worktrees, so linked worktrees count as
+/// enabled even though their checkout has no `.gitclaude` of its own.
+const ENABLED_MARKER: &str = "enabled";
+
+/// Decide whether hooks should act in `repo_root`, returning why not.
+///
+/// Deliberately avoids git2 so excluded repos cost almost nothing: patterns
+/// are matched against the repo root and against remote URLs read straight
+/// from the git config file.
+pub fn excluded_reason(config: &GlobalConfig, repo_root: &Path, from_hook: bool) -> Option<String> {
+    let root = repo_root.to_string_lossy().to_string();
+    let remotes = remote_urls(repo_root);
+
+    let matches = |pattern: &str| {
+        let expanded = expand_home(pattern).to_string_lossy().to_string();
+        let Ok(glob) = glob::Pattern::new(&expanded) else {
+            tracing::warn!("Invalid repo pattern '{}'", pattern);
+            return false;
+        };
+
+        glob.matches(&root) || remotes.iter().any(|url| glob.matches(url))
+    };
+
+    if let Some(pattern) = config.ignore_repos.iter().find(|p| matches(p)) {
+        return Some(format!("repo matches ignore_repos pattern '{}'", pattern));
+    }
+
+    if !config.allow_repos.is_empty() && !config.allow_repos.iter().any(|p| matches(p)) {
+        return Some("repo does not match any allow_repos pattern".to_string());
+    }
+
+    // Global hooks fire everywhere; only act in enabled repos unless listening globally
+    if from_hook && !config.listen_globally && !is_enabled(repo_root) {
+        return Some("gitclaude is not enabled in this repo".to_string());
+    }
+
+    None
+}
+
+/// Whether gitclaude was enabled in the repo `repo_root` belongs to, from
+/// any of its worktrees. A repo config alone doesn't count: `disable
+/// --keep-config` leaves one behind.
+pub fn is_enabled(repo_root: &Path) -> bool {
+    common_git_dir(repo_root).is_some_and(|dir| dir.join("gitclaude").join(ENABLED_MARKER).exists())
+}
+
+/// Record whether gitclaude is enabled for every worktree of the repo
+pub fn set_enabled(repo_root: &Path, enabled: bool) -> Result<()> {
+    let marker = config::state_dir(repo_root)?.join(ENABLED_MARKER);
+
+    if enabled {
+        std::fs::create_dir_all(marker.parent().unwrap_or(repo_root))?;
+        std::fs::write(&marker, "")?;
+    } else if marker.exists() {
+        std::fs::remove_file(&marker)?;
+    }
+    Ok(())
+}
+
+/// Remote URLs of a repo, normalized to `host/owner/name`
+fn remote_urls(repo_root: &Path) -> Vec<String> {
+    let Some(git_dir) = common_git_dir(repo_root) else {
+        return vec![];
+    };
+
+    let Ok(content) = std::fs::read_to_string(git_dir.join("config")) else {
+        return vec![];
+    };
+
+    content
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.trim().split_once('=')?;
+            (key.trim() == "url").then(|| normalize_remote_url(value.trim()))
+        })
+        .collect()
+}
+
+/// Locate the git dir holding the shared config, following `.git` files
+/// (worktrees, submodules) and `commondir`
+fn common_git_dir(repo_root: &Path) -> Option<PathBuf> {
+    let dot_git = repo_root.join(".git");
+    if dot_git.is_dir() {
+        return Some(dot_git);
+    }
+
+    let content = std::fs::read_to_string(&dot_git).ok()?;
+    let git_dir = repo_root.join(content.trim().strip_prefix("gitdir:")?.trim());
+
+    match std::fs::read_to_string(git_dir.join("commondir")) {
+        Ok(common) => Some(git_dir.join(common.trim())),
+        Err(_) => Some(git_dir),
+    }
+}
+
+/// Turn `git@github.com:acme/app.git` or `https://github.com/acme/app.git`
+/// into `github.com/acme/app`
+fn normalize_remote_url(url: &str) -> String {
+    let url = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url);
+    let url = url.split_once('@').map(|(_, rest)| rest).unwrap_or(url);
+
+    // scp-like syntax uses ':' between host and path
+    let url = match url.split_once(':') {
+        Some((host, path)) if !path.starts_with("//") && !host.contains('/') => {
+            let path = path.trim_start_matches(|c: char| c.is_ascii_digit()).trim_start_matches('/');
+            format!("{}/{}", host, path)
+        }
+        _ => url.to_string(),
+    };
+
+    url.trim_end_matches('/').trim_end_matches(".git").to_string()
+}
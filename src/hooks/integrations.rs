@@ -0,0 +1,340 @@
+use anyhow::{Context, Result};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use super::manager::installed_hooks;
+use super::scripts::{hook_command, reads_stdin, HOOK_BEGIN_MARKER, HOOK_END_MARKER};
+
+/// Hook frameworks gitclaude can register itself with instead of writing
+/// to `.git/hooks`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookManager {
+    /// pre-commit.com, configured in `.pre-commit-config.yaml`
+    PreCommit,
+    /// husky, one script per hook in `.husky/`
+    Husky,
+    /// lefthook, configured in `lefthook.yml`
+    Lefthook,
+}
+
+/// How gitclaude's hooks are wired up in a repo
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntegrationMode {
+    /// Scripts in the git hooks directory
+    GitHooks,
+    /// A step in a hook framework's config
+    Manager(HookManager),
+    /// Not installed
+    None,
+}
+
+const ALL_MANAGERS: &[HookManager] = &[HookManager::PreCommit, HookManager::Husky, HookManager::Lefthook];
+
+const LEFTHOOK_FILES: &[&str] = &["lefthook.yml", "lefthook.yaml", ".lefthook.yml", ".lefthook.yaml"];
+
+impl HookManager {
+    /// Config file or directory the framework reads
+    pub fn config_path(&self, repo_root: &Path) -> PathBuf {
+        match self {
+            HookManager::PreCommit => repo_root.join(".pre-commit-config.yaml"),
+            HookManager::Husky => repo_root.join(".husky"),
+            HookManager::Lefthook => LEFTHOOK_FILES
+                .iter()
+                .map(|f| repo_root.join(f))
+                .find(|p| p.exists())
+                .unwrap_or_else(|| repo_root.join(LEFTHOOK_FILES[0])),
+        }
+    }
+
+    /// Command that makes the framework pick up new hook types, if any
+    pub fn install_hint(&self, events: &[String]) -> Option<String> {
+        match self {
+            HookManager::PreCommit => {
+                let types: Vec<String> = events.iter().map(|e| format!("--hook-type {}", e)).collect();
+                Some(format!("pre-commit install {}", types.join(" ")))
+            }
+            HookManager::Husky => None,
+            HookManager::Lefthook => Some("lefthook install".to_string()),
+        }
+    }
+}
+
+impl fmt::Display for HookManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HookManager::PreCommit => write!(f, "pre-commit"),
+            HookManager::Husky => write!(f, "husky"),
+            HookManager::Lefthook => write!(f, "lefthook"),
+        }
+    }
+}
+
+impl fmt::Display for IntegrationMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntegrationMode::GitHooks => write!(f, "git hooks"),
+            IntegrationMode::Manager(manager) => write!(f, "{}", manager),
+            IntegrationMode::None => write!(f, "not installed"),
+        }
+    }
+}
+
+/// Find the hook framework a repo uses, if any
+pub fn detect_hook_manager(repo_root: &Path) -> Option<HookManager> {
+    ALL_MANAGERS
+        .iter()
+        .copied()
+        .find(|m| m.config_path(repo_root).exists())
+}
+
+/// Report how gitclaude is hooked into a repo
+pub fn integration_mode(repo_root: &Path) -> Result<IntegrationMode> {
+    for manager in ALL_MANAGERS {
+        if is_registered(*manager, repo_root) {
+            return Ok(IntegrationMode::Manager(*manager));
+        }
+    }
+
+    if !installed_hooks(repo_root)?.is_empty() {
+        return Ok(IntegrationMode::GitHooks);
+    }
+
+    Ok(IntegrationMode::None)
+}
+
+/// Add gitclaude as a step for each event in the framework's config,
+/// replacing any steps added before. Returns the file that was changed.
+pub fn register_with_manager(manager: HookManager, repo_root: &Path, events: &[String]) -> Result<PathBuf> {
+    let path = manager.config_path(repo_root);
+
+    match manager {
+        HookManager::PreCommit => {
+            let content = std::fs::read_to_string(&path)?;
+            let updated = register_pre_commit(&strip_marked_blocks(&content), events)
+                .with_context(|| format!("Failed to update {}", path.display()))?;
+            std::fs::write(&path, updated)?;
+        }
+        HookManager::Husky => {
+            for event in events {
+                register_husky(&path.join(event), event)?;
+            }
+        }
+        HookManager::Lefthook => {
+            let mut content = strip_marked_blocks(&std::fs::read_to_string(&path)?);
+            for event in events {
+                content = register_lefthook(&content, event);
+            }
+            std::fs::write(&path, content)?;
+        }
+    }
+
+    Ok(path)
+}
+
+/// Remove gitclaude's steps from the framework's config. Returns whether
+/// anything was removed.
+pub fn unregister_from_manager(manager: HookManager, repo_root: &Path) -> Result<bool> {
+    let path = manager.config_path(repo_root);
+
+    let files = match manager {
+        HookManager::Husky => husky_scripts(&path)?,
+        _ if path.is_file() => vec![path],
+        _ => vec![],
+    };
+
+    let mut removed = false;
+    for file in files {
+        let content = std::fs::read_to_string(&file)?;
+        if !content.contains(HOOK_BEGIN_MARKER) {
+            continue;
+        }
+
+        let stripped = strip_marked_blocks(&content);
+        if manager == HookManager::Husky && stripped.trim().is_empty() {
+            // The script only ever held our step
+            std::fs::remove_file(&file)?;
+        } else {
+            std::fs::write(&file, stripped)?;
+        }
+        removed = true;
+    }
+
+    Ok(removed)
+}
+
+fn is_registered(manager: HookManager, repo_root: &Path) -> bool {
+    let path = manager.config_path(repo_root);
+
+    let files = match manager {
+        HookManager::Husky => husky_scripts(&path).unwrap_or_default(),
+        _ => vec![path],
+    };
+
+    files.iter().any(|f| {
+        std::fs::read_to_string(f)
+            .map(|c| c.contains(HOOK_BEGIN_MARKER))
+            .unwrap_or(false)
+    })
+}
+
+fn husky_scripts(dir: &Path) -> Result<Vec<PathBuf>> {
+    if !dir.is_dir() {
+        return Ok(vec![]);
+    }
+
+    let mut files = vec![];
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_file() {
+            files.push(path);
+        }
+    }
+
+    Ok(files)
+}
+
+/// Insert a `repo: local` entry right under `repos:`, matching the
+/// indentation of the existing entries
+fn register_pre_commit(content: &str, events: &[String]) -> Result<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let repos = lines
+        .iter()
+        .position(|l| l.trim_end() == "repos:")
+        .context("no top-level `repos:` list")?;
+
+    let indent = lines[repos + 1..]
+        .iter()
+        .find(|l| l.trim_start().starts_with("- "))
+        .map(|l| l.len() - l.trim_start().len())
+        .unwrap_or(0);
+    let pad = " ".repeat(indent);
+
+    let mut block = vec![
+        format!("{}{}", pad, HOOK_BEGIN_MARKER),
+        format!("{}- repo: local", pad),
+        format!("{}  hooks:", pad),
+    ];
+    for event in events {
+        block.push(format!("{}    - id: gitclaude-{}", pad, event));
+        block.push(format!("{}      name: gitclaude {}", pad, event));
+        block.push(format!("{}      entry: {}", pad, hook_command(event)));
+        block.push(format!("{}      language: system", pad));
+        block.push(format!("{}      stages: [{}]", pad, event));
+        block.push(format!("{}      always_run: true", pad));
+        block.push(format!("{}      pass_filenames: false", pad));
+    }
+    block.push(format!("{}{}", pad, HOOK_END_MARKER));
+
+    let mut out: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+    out.splice(repos + 1..repos + 1, block);
+
+    Ok(out.join("\n") + "\n")
+}
+
+/// Append our step to a husky hook script, creating it if needed
+fn register_husky(script: &Path, event: &str) -> Result<()> {
+    let existing = std::fs::read_to_string(script).unwrap_or_default();
+    let mut content = strip_marked_blocks(&existing);
+
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    content.push_str(&format!(
+        "{}\n{} \"$@\"\n{}\n",
+        HOOK_BEGIN_MARKER,
+        hook_command(event),
+        HOOK_END_MARKER
+    ));
+
+    std::fs::write(script, content)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mut perms = std::fs::metadata(script)?.permissions();
+        perms.set_mode(perms.mode() | 0o755);
+        std::fs::set_permissions(script, perms)?;
+    }
+
+    Ok(())
+}
+
+/// Add a `gitclaude` command to the event's `commands`, adding the event
+/// section if the file has none
+fn register_lefthook(content: &str, event: &str) -> String {
+    let mut lines: Vec<String> = content.lines().map(String::from).collect();
+    let header = format!("{}:", event);
+
+    let command = |pad: &str| {
+        let mut command = vec![
+            format!("{}gitclaude:", pad),
+            format!("{}  run: {} {{0}}", pad, hook_command(event)),
+        ];
+        if reads_stdin(event) {
+            command.push(format!("{}  use_stdin: true", pad));
+        }
+        command
+    };
+    let step = |pad: &str| {
+        let mut step = vec![format!("{}{}", pad, HOOK_BEGIN_MARKER)];
+        step.extend(command(pad));
+        step.push(format!("{}{}", pad, HOOK_END_MARKER));
+        step
+    };
+
+    let Some(start) = lines.iter().position(|l| l.trim_end() == header) else {
+        // A section of our own, removed as a whole on disable
+        lines.push(HOOK_BEGIN_MARKER.to_string());
+        lines.push(header);
+        lines.push("  commands:".to_string());
+        lines.extend(command("    "));
+        lines.push(HOOK_END_MARKER.to_string());
+        return lines.join("\n") + "\n";
+    };
+
+    // The event's section ends at the next top-level key
+    let end = lines[start + 1..]
+        .iter()
+        .position(|l| !l.is_empty() && !l.starts_with(' ') && !l.starts_with('#'))
+        .map(|i| start + 1 + i)
+        .unwrap_or(lines.len());
+
+    let commands = lines[start + 1..end]
+        .iter()
+        .position(|l| l.trim_end().trim_start() == "commands:")
+        .map(|i| start + 1 + i);
+
+    match commands {
+        Some(at) => {
+            let indent = lines[at].len() - lines[at].trim_start().len();
+            lines.splice(at + 1..at + 1, step(&" ".repeat(indent + 2)));
+        }
+        None => {
+            let mut block = vec!["  commands:".to_string()];
+            block.extend(step("    "));
+            lines.splice(start + 1..start + 1, block);
+        }
+    }
+
+    lines.join("\n") + "\n"
+}
+
+/// Remove every block between our begin and end markers
+fn strip_marked_blocks(content: &str) -> String {
+    let mut out = vec![];
+    let mut inside = false;
+
+    for line in content.lines() {
+        match line.trim() {
+            l if l == HOOK_BEGIN_MARKER => inside = true,
+            l if l == HOOK_END_MARKER => inside = false,
+            _ if !inside => out.push(line),
+            _ => {}
+        }
+    }
+
+    if out.is_empty() {
+        return String::new();
+    }
+    out.join("\n") + "\n"
+}
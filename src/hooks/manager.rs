@@ -1,45 +1,131 @@
-use anyhow::Result;
-use std::path::Path;
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+use super::scripts::{
+    generate_blocking_hook_script, generate_chained_hook_script, generate_hook_script,
+    is_blocking_hook, is_gitclaude_hook, is_stale_hook, HOOK_BACKUP_SUFFIX,
+};
+
+/// What happened to a hook file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookChange {
+    /// No hook existed, ours was written
+    Installed,
+    /// An existing hook was backed up and now runs before ours
+    Chained,
+    /// Our hook was rewritten in place
+    Updated,
+    /// Our hook was removed and the original put back
+    Restored,
+    /// Our hook was removed, there was nothing to restore
+    Removed,
+    /// The hook was changed by someone else and left alone
+    Skipped,
+}
+
+/// Directory git runs a repo's hooks from.
+///
+/// This is `core.hooksPath` when set, otherwise `hooks/` in the common git
+/// dir, which linked worktrees share and which lives under
+/// `.git/modules/<name>` for submodules.
+pub fn hooks_dir(repo_path: &Path) -> Result<PathBuf> {
+    let repo = git2::Repository::open(repo_path)
+        .with_context(|| format!("Failed to open repository at {}", repo_path.display()))?;
+    let config = repo.config()?;
+
+    if let Ok(path) = config.get_path("core.hooksPath") {
+        if let Ok(entry) = config.get_entry("core.hooksPath") {
+            if !matches!(entry.level(), git2::ConfigLevel::Local | git2::ConfigLevel::App) {
+                tracing::warn!(
+                    "core.hooksPath is set outside this repo, hooks in {} are shared with other repos",
+                    path.display()
+                );
+            }
+        }
+
+        // Git resolves a relative hooksPath against the worktree hooks run in
+        if path.is_relative() {
+            let base = repo.workdir().unwrap_or_else(|| repo.path());
+            return Ok(base.join(path));
+        }
+        return Ok(path);
+    }
+
+    Ok(crate::config::git_common_dir(repo_path)?.join("hooks"))
+}
 
 /// Install hooks for a repository
-pub fn install_hooks(repo_path: &Path, events: &[String]) -> Result<()> {
-    let hooks_dir = repo_path.join(".git").join("hooks");
+pub fn install_hooks(repo_path: &Path, events: &[String]) -> Result<Vec<(String, HookChange)>> {
+    let hooks_dir = hooks_dir(repo_path)?;
+    std::fs::create_dir_all(&hooks_dir)?;
+
+    events
+        .iter()
+        .map(|event| Ok((event.clone(), install_hook(&hooks_dir, event)?)))
+        .collect()
+}
+
+/// Remove hooks from a repository, putting back any hook that was there
+/// before gitclaude
+pub fn remove_hooks(repo_path: &Path, events: &[String]) -> Result<Vec<(String, HookChange)>> {
+    let hooks_dir = hooks_dir(repo_path)?;
 
+    let mut changes = vec![];
     for event in events {
-        let hook_path = hooks_dir.join(event);
-        let script = super::scripts::generate_hook_script(event);
-
-        std::fs::write(&hook_path, script)?;
-
-        // Make executable
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            let mut perms = std::fs::metadata(&hook_path)?.permissions();
-            perms.set_mode(0o755);
-            std::fs::set_permissions(&hook_path, perms)?;
+        if let Some(change) = remove_hook(&hooks_dir, event)? {
+            changes.push((event.clone(), change));
         }
     }
 
-    Ok(())
+    Ok(changes)
 }
 
-/// Remove hooks from a repository
-pub fn remove_hooks(repo_path: &Path, events: &[String]) -> Result<()> {
-    let hooks_dir = repo_path.join(".git").join("hooks");
+/// Rewrite the gitclaude hooks of a repo that an older version generated or
+/// that call another gitclaude binary. Returns the hooks rewritten
+pub fn upgrade_hooks(repo_path: &Path) -> Result<Vec<String>> {
+    let hooks_dir = hooks_dir(repo_path)?;
 
-    for event in events {
-        let hook_path = hooks_dir.join(event);
-        if hook_path.exists() {
-            // Check if it's our hook before removing
-            let content = std::fs::read_to_string(&hook_path)?;
-            if content.contains("gitclaude") {
-                std::fs::remove_file(hook_path)?;
-            }
+    let mut upgraded = vec![];
+    for event in gitclaude_hooks_in(&hooks_dir)? {
+        let content = std::fs::read_to_string(hooks_dir.join(&event))?;
+        if is_stale_hook(&content) {
+            install_hook(&hooks_dir, &event)?;
+            upgraded.push(event);
         }
     }
 
-    Ok(())
+    Ok(upgraded)
+}
+
+/// Events that currently have a gitclaude hook installed
+pub fn installed_hooks(repo_path: &Path) -> Result<Vec<String>> {
+    gitclaude_hooks_in(&hooks_dir(repo_path)?)
+}
+
+/// Names of the gitclaude hooks in `hooks_dir`, whether or not git runs
+/// hooks from there
+pub fn gitclaude_hooks_in(hooks_dir: &Path) -> Result<Vec<String>> {
+    if !hooks_dir.is_dir() {
+        return Ok(vec![]);
+    }
+
+    let mut events = vec![];
+    for entry in std::fs::read_dir(hooks_dir)? {
+        let path = entry?.path();
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else { continue };
+
+        if name.ends_with(HOOK_BACKUP_SUFFIX) || name.ends_with(".sample") || !path.is_file() {
+            continue;
+        }
+
+        let content = std::fs::read_to_string(&path).unwrap_or_default();
+        if is_gitclaude_hook(&content) {
+            events.push(name.to_string());
+        }
+    }
+
+    events.sort();
+    Ok(events)
 }
 
 /// Install global hooks
@@ -51,17 +137,7 @@ pub fn install_global_hooks(events: &[String]) -> Result<()> {
     std::fs::create_dir_all(&hooks_dir)?;
 
     for event in events {
-        let hook_path = hooks_dir.join(event);
-        let script = super::scripts::generate_hook_script(event);
-        std::fs::write(&hook_path, script)?;
-
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            let mut perms = std::fs::metadata(&hook_path)?.permissions();
-            perms.set_mode(0o755);
-            std::fs::set_permissions(&hook_path, perms)?;
-        }
+        install_hook(&hooks_dir, event)?;
     }
 
     // Set global hooks path
@@ -71,3 +147,110 @@ pub fn install_global_hooks(events: &[String]) -> Result<()> {
 
     Ok(())
 }
+
+/// Write our hook for `event`, chaining to a hook that is already there.
+///
+/// The original is kept byte for byte (and with its permissions) next to the
+/// hook so [`remove_hook`] can put it back exactly.
+fn install_hook(hooks_dir: &Path, event: &str) -> Result<HookChange> {
+    let hook_path = hooks_dir.join(event);
+    let backup_path = backup_path(hooks_dir, event);
+
+    let change = match std::fs::read(&hook_path) {
+        Ok(content) if is_gitclaude_hook(&String::from_utf8_lossy(&content)) => HookChange::Updated,
+        Ok(_) => {
+            if backup_path.exists() {
+                anyhow::bail!(
+                    "{} was changed after gitclaude was enabled and {} already exists; \
+                     merge them by hand before enabling again",
+                    hook_path.display(),
+                    backup_path.display()
+                );
+            }
+
+            std::fs::copy(&hook_path, &backup_path)
+                .with_context(|| format!("Failed to back up {}", hook_path.display()))?;
+            HookChange::Chained
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => HookChange::Installed,
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", hook_path.display())),
+    };
+
+    let blocking = is_blocking_hook(event);
+    let script = if backup_path.exists() {
+        generate_chained_hook_script(event, blocking)
+    } else if blocking {
+        generate_blocking_hook_script(event)
+    } else {
+        generate_hook_script(event)
+    };
+
+    std::fs::write(&hook_path, script)?;
+    make_executable(&hook_path)?;
+
+    Ok(change)
+}
+
+fn remove_hook(hooks_dir: &Path, event: &str) -> Result<Option<HookChange>> {
+    let hook_path = hooks_dir.join(event);
+    let backup_path = backup_path(hooks_dir, event);
+
+    if !hook_path.exists() {
+        if backup_path.exists() {
+            std::fs::rename(&backup_path, &hook_path)?;
+            return Ok(Some(HookChange::Restored));
+        }
+        return Ok(None);
+    }
+
+    let content = std::fs::read_to_string(&hook_path).unwrap_or_default();
+    if !is_gitclaude_hook(&content) {
+        if backup_path.exists() {
+            tracing::warn!(
+                "{} no longer contains the gitclaude hook, leaving it and {} alone",
+                hook_path.display(),
+                backup_path.display()
+            );
+        }
+        return Ok(Some(HookChange::Skipped));
+    }
+
+    if backup_path.exists() {
+        std::fs::rename(&backup_path, &hook_path)?;
+        Ok(Some(HookChange::Restored))
+    } else {
+        std::fs::remove_file(&hook_path)?;
+        Ok(Some(HookChange::Removed))
+    }
+}
+
+fn backup_path(hooks_dir: &Path, event: &str) -> PathBuf {
+    hooks_dir.join(format!("{}{}", event, HOOK_BACKUP_SUFFIX))
+}
+
+/// Whether git can run the hook at `path`
+pub fn is_executable(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::metadata(path).is_ok_and(|m| m.permissions().mode() & 0o111 != 0)
+    }
+
+    #[cfg(not(unix))]
+    path.is_file()
+}
+
+fn make_executable(path: &Path) -> Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mut perms = std::fs::metadata(path)?.permissions();
+        perms.set_mode(0o755);
+        std::fs::set_permissions(path, perms)?;
+    }
+
+    #[cfg(not(unix))]
+    let _ = path;
+
+    Ok(())
+}
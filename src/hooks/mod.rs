@@ -1,7 +1,13 @@
 //! Git hooks management
 
+mod filter;
+mod integrations;
 mod manager;
+mod registry;
 mod scripts;
 
+pub use filter::*;
+pub use integrations::*;
 pub use manager::*;
+pub use registry::*;
 pub use scripts::*;
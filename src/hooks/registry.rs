@@ -0,0 +1,68 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Repos gitclaude installed hook scripts in, so `gitclaude hooks upgrade
+/// --all` can find them again
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Registry {
+    #[serde(default)]
+    repos: Vec<PathBuf>,
+}
+
+/// Where the registry is kept, next to gitclaude's other user data
+pub fn registry_path() -> Result<PathBuf> {
+    let dirs = directories::ProjectDirs::from("", "", "gitclaude")
+        .context("Could not determine data directory")?;
+
+    Ok(dirs.data_dir().join("repos.json"))
+}
+
+/// Registered repos, in the order they were added
+pub fn registered_repos() -> Result<Vec<PathBuf>> {
+    Ok(load()?.repos)
+}
+
+/// Remember `repo_root`; registering it again is a no-op
+pub fn register_repo(repo_root: &Path) -> Result<()> {
+    let repo_root = repo_root.canonicalize().unwrap_or_else(|_| repo_root.to_path_buf());
+    let mut registry = load()?;
+
+    if !registry.repos.contains(&repo_root) {
+        registry.repos.push(repo_root);
+        save(&registry)?;
+    }
+    Ok(())
+}
+
+/// Forget `repo_root`
+pub fn unregister_repo(repo_root: &Path) -> Result<()> {
+    let repo_root = repo_root.canonicalize().unwrap_or_else(|_| repo_root.to_path_buf());
+    let mut registry = load()?;
+
+    let count = registry.repos.len();
+    registry.repos.retain(|r| *r != repo_root);
+    if registry.repos.len() != count {
+        save(&registry)?;
+    }
+    Ok(())
+}
+
+fn load() -> Result<Registry> {
+    let path = registry_path()?;
+    match std::fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str(&content).with_context(|| format!("Failed to parse {}", path.display())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Registry::default()),
+        Err(e) => Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    }
+}
+
+fn save(registry: &Registry) -> Result<()> {
+    let path = registry_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let content = serde_json::to_string_pretty(registry)?;
+    std::fs::write(&path, content).with_context(|| format!("Failed to write {}", path.display()))
+}
@@ -1,45 +1,244 @@
+use std::path::PathBuf;
+
+/// First line of the section gitclaude owns in a hook file
+pub const HOOK_BEGIN_MARKER: &str = "# >>> gitclaude >>>";
+
+/// Last line of the section gitclaude owns in a hook file
+pub const HOOK_END_MARKER: &str = "# <<< gitclaude <<<";
+
+/// Suffix of the copy kept of a hook that existed before gitclaude
+pub const HOOK_BACKUP_SUFFIX: &str = ".gitclaude-backup";
+
+/// Version of the generated hook scripts, bumped whenever they change so
+/// `gitclaude hooks upgrade` rewrites older ones. Scripts from before the
+/// version stamp count as version 1
+pub const HOOK_SCRIPT_VERSION: u32 = 2;
+
+const VERSION_STAMP: &str = "# gitclaude-hook-version: ";
+const BINARY_STAMP: &str = "# gitclaude-binary: ";
+
+/// Hooks git feeds data on stdin
+const STDIN_HOOKS: &[&str] = &[
+    "pre-push",
+    "post-rewrite",
+    "reference-transaction",
+    "pre-receive",
+    "post-receive",
+    "proc-receive",
+];
+
+/// Hooks whose exit code can stop git, run with `--blocking`
+const BLOCKING_HOOKS: &[&str] = &["pre-commit"];
+
+/// Whether git passes data to the hook on stdin
+pub fn reads_stdin(event: &str) -> bool {
+    STDIN_HOOKS.contains(&event)
+}
+
+/// Whether the hook runs gitclaude in blocking mode
+pub fn is_blocking_hook(event: &str) -> bool {
+    BLOCKING_HOOKS.contains(&event)
+}
+
+/// The `gitclaude run` command a hook for `event` calls, without git's
+/// arguments
+pub fn hook_command(event: &str) -> String {
+    let blocking = if is_blocking_hook(event) { " --blocking" } else { "" };
+    format!("gitclaude run {} --from-hook{}", event, blocking)
+}
+
 /// Generate a hook script for the given event
 pub fn generate_hook_script(event: &str) -> String {
-    format!(
-        r#"#!/bin/sh
-# Generated by gitclaude - do not edit manually
-
-# Check if gitclaude is installed
-if ! command -v gitclaude &> /dev/null; then
-    echo "gitclaude not found, skipping hook"
-    exit 0
-fi
-
-# Run gitclaude for this event
-gitclaude run {event} "$@"
-"#,
-        event = event
-    )
+    render_hook_script(event, false, false)
 }
 
 /// Generate a blocking hook script (for pre-commit)
 pub fn generate_blocking_hook_script(event: &str) -> String {
-    format!(
-        r#"#!/bin/sh
-# Generated by gitclaude - do not edit manually
-
-# Check if gitclaude is installed
-if ! command -v gitclaude &> /dev/null; then
-    echo "gitclaude not found, skipping hook"
-    exit 0
+    render_hook_script(event, true, false)
+}
+
+/// Generate a hook script that first runs the original hook, saved next to
+/// it with [`HOOK_BACKUP_SUFFIX`], and only runs gitclaude if it succeeds
+pub fn generate_chained_hook_script(event: &str, blocking: bool) -> String {
+    render_hook_script(event, blocking, true)
+}
+
+/// Whether a hook file was written by gitclaude, including scripts from
+/// versions that predate the section markers
+pub fn is_gitclaude_hook(content: &str) -> bool {
+    content.contains(HOOK_BEGIN_MARKER) || content.contains("# Generated by gitclaude")
+}
+
+/// The gitclaude binary generated hooks call: the one running now, by the
+/// path it was started through.
+///
+/// `current_exe` resolves symlinks, which would pin hooks to e.g. a Homebrew
+/// Cellar path that is gone after the next upgrade, while the symlink that
+/// was invoked keeps pointing at whichever version is installed.
+pub fn gitclaude_binary() -> PathBuf {
+    let current = std::env::current_exe().ok();
+    let same_binary = |path: &PathBuf| {
+        let resolved = path.canonicalize().ok();
+        resolved.is_some() && resolved == current.as_ref().and_then(|c| c.canonicalize().ok())
+    };
+
+    invoked_path()
+        .filter(same_binary)
+        .or(current)
+        .unwrap_or_else(|| PathBuf::from("gitclaude"))
+}
+
+/// Absolute path gitclaude was started through, with symlinks left in place
+fn invoked_path() -> Option<PathBuf> {
+    let arg0 = PathBuf::from(std::env::args_os().next()?);
+
+    if arg0.components().count() > 1 {
+        // Drop `..` lexically like a shell would; the caller checks the
+        // result is still this binary
+        let mut path = PathBuf::new();
+        for component in std::path::absolute(&arg0).ok()?.components() {
+            match component {
+                std::path::Component::ParentDir => {
+                    path.pop();
+                }
+                other => path.push(other),
+            }
+        }
+        Some(path)
+    } else {
+        which::which(&arg0).ok()
+    }
+}
+
+/// What a gitclaude hook script was generated with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HookStamp {
+    pub version: u32,
+    /// Absolute path of the gitclaude it calls, `None` before version 2
+    pub binary: Option<PathBuf>,
+}
+
+/// Read the version and binary stamped into a gitclaude hook script
+pub fn read_hook_stamp(content: &str) -> HookStamp {
+    let version = content
+        .lines()
+        .find_map(|l| l.strip_prefix(VERSION_STAMP))
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(1);
+    let binary = content
+        .lines()
+        .find_map(|l| l.strip_prefix(BINARY_STAMP))
+        .map(|b| PathBuf::from(b.trim_end()));
+
+    HookStamp { version, binary }
+}
+
+/// Whether a gitclaude hook script should be generated again: it is from an
+/// older version, or this version calling a gitclaude other than this one.
+/// Scripts from newer versions are left alone
+pub fn is_stale_hook(content: &str) -> bool {
+    let stamp = read_hook_stamp(content);
+    stamp.version < HOOK_SCRIPT_VERSION
+        || (stamp.version == HOOK_SCRIPT_VERSION && stamp.binary != Some(gitclaude_binary()))
+}
+
+/// Quote `value` for a POSIX shell
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+fn render_hook_script(event: &str, blocking: bool, chained: bool) -> String {
+    let binary = gitclaude_binary();
+    let mut script = format!(
+        "#!/bin/sh\n{}\n# Generated by gitclaude - do not edit manually\n{}{}\n{}{}\n",
+        HOOK_BEGIN_MARKER,
+        VERSION_STAMP,
+        HOOK_SCRIPT_VERSION,
+        BINARY_STAMP,
+        binary.display()
+    );
+
+    // Hooks that read stdin get it saved once so both hooks see all of it
+    let stdin = if chained && reads_stdin(event) {
+        script.push_str(
+            r#"
+stdin_file=$(mktemp "${TMPDIR:-/tmp}/gitclaude.XXXXXX" 2>/dev/null) ||
+    stdin_file="${TMPDIR:-/tmp}/gitclaude.$$"
+trap 'rm -f "$stdin_file"' EXIT
+cat > "$stdin_file" || exit 1
+"#,
+        );
+        r#" < "$stdin_file""#
+    } else {
+        ""
+    };
+
+    if chained {
+        script.push_str(&format!(
+            r#"
+# Run the hook that was here before gitclaude, keeping its exit code
+original="$(dirname "$0")/{event}{suffix}"
+if [ -x "$original" ]; then
+    "$original" "$@"{stdin} || exit $?
+fi
+"#,
+            event = event,
+            suffix = HOOK_BACKUP_SUFFIX,
+            stdin = stdin,
+        ));
+    }
+
+    // Runs for every ref update in three phases; only a committed
+    // transaction can have created a tag
+    if event == "reference-transaction" {
+        script.push_str("\n[ \"$1\" = committed ] || exit 0\n");
+    }
+
+    script.push_str(&format!(
+        r#"
+# The gitclaude that installed this hook, else the one on PATH (GUI git
+# clients often run hooks with a different PATH)
+gitclaude={binary}
+if [ ! -x "$gitclaude" ]; then
+    gitclaude=$(command -v gitclaude) || {{
+        echo "gitclaude not found, skipping hook" >&2
+        exit 0
+    }}
 fi
+"#,
+        binary = shell_quote(&binary.to_string_lossy()),
+    ));
 
+    if blocking {
+        script.push_str(&format!(
+            r#"
 # Run gitclaude for this event (blocking mode)
-gitclaude run {event} --blocking "$@"
+"$gitclaude" run {event} --from-hook --blocking "$@"{stdin}
 exit_code=$?
 
-if [ $exit_code -ne 0 ]; then
-    echo "gitclaude validation failed"
+if [ "$exit_code" -ne 0 ]; then
+    echo "gitclaude validation failed" >&2
     exit 1
 fi
 
 exit 0
 "#,
-        event = event
-    )
+            event = event,
+            stdin = stdin,
+        ));
+    } else {
+        script.push_str(&format!(
+            r#"
+# Run gitclaude for this event; only blocking hooks may stop git
+"$gitclaude" run {event} --from-hook "$@"{stdin}
+exit 0
+"#,
+            event = event,
+            stdin = stdin,
+        ));
+    }
+
+    script.push_str(HOOK_END_MARKER);
+    script.push('\n');
+    script
 }
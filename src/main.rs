@@ -17,6 +17,10 @@ struct Cli {
     #[arg(short, long, global = true)]
     verbose: bool,
 
+    /// Override a config key for this invocation (repeatable)
+    #[arg(short = 'c', global = true, value_name = "KEY=VALUE")]
+    config_overrides: Vec<String>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -42,17 +46,28 @@ enum Commands {
         /// Keep configuration file
         #[arg(short, long)]
         keep_config: bool,
+
+        /// Also delete templates, responses and everything else in .gitclaude
+        #[arg(long, conflicts_with = "keep_config")]
+        purge: bool,
     },
 
     /// Show or edit configuration
     Config {
         /// Edit global configuration
-        #[arg(short, long)]
+        #[arg(short, long, global = true)]
         global: bool,
 
         /// Open in editor
         #[arg(short, long)]
         edit: bool,
+
+        /// Annotate each value with where it came from
+        #[arg(short, long)]
+        origin: bool,
+
+        #[command(subcommand)]
+        action: Option<ConfigActions>,
     },
 
     /// Show current status
@@ -62,10 +77,13 @@ enum Commands {
         verbose: bool,
     },
 
+    /// Check why hooks might not reach Claude, with a fix for each problem
+    Doctor,
+
     /// View response history
     Logs {
         /// Number of entries to show
-        #[arg(short, long, default_value = "10")]
+        #[arg(short = 'n', long, default_value = "10")]
         count: usize,
 
         /// Filter by event type
@@ -81,6 +99,40 @@ enum Commands {
         /// Dry run (show what would be sent)
         #[arg(short, long)]
         dry_run: bool,
+
+        /// Profile to use instead of the configured one
+        #[arg(short, long)]
+        profile: Option<String>,
+
+        /// Set by generated hook scripts
+        #[arg(long, hide = true)]
+        from_hook: bool,
+
+        /// Set on the background run of a deferred event
+        #[arg(long, hide = true)]
+        deferred: bool,
+
+        /// Wait for a PASS/WARN/FAIL verdict and exit non-zero on FAIL
+        #[arg(long)]
+        blocking: bool,
+
+        /// Arguments git passes to the hook
+        #[arg(allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
+    /// Draft release notes for a tag, from the commits since the previous one
+    ReleaseNotes {
+        /// Tag to write release notes for
+        tag: String,
+
+        /// Dry run (show what would be sent)
+        #[arg(short, long)]
+        dry_run: bool,
+
+        /// Profile to use instead of the configured one
+        #[arg(short, long)]
+        profile: Option<String>,
     },
 
     /// Manage templates
@@ -88,6 +140,12 @@ enum Commands {
         #[command(subcommand)]
         action: Option<TemplateActions>,
     },
+
+    /// Manage installed hook scripts
+    Hooks {
+        #[command(subcommand)]
+        action: HookActions,
+    },
 }
 
 #[derive(Subcommand)]
@@ -102,6 +160,40 @@ enum TemplateActions {
     Show { name: String },
 }
 
+#[derive(Subcommand)]
+enum HookActions {
+    /// Rewrite hook scripts generated by an older gitclaude
+    Upgrade {
+        /// Upgrade every repo gitclaude was enabled in, not just this one
+        #[arg(long)]
+        all: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigActions {
+    /// Check config files for errors and unknown keys
+    Validate {
+        /// Treat warnings as errors
+        #[arg(long)]
+        strict: bool,
+    },
+    /// Print the effective value of a dotted key
+    Get { key: String },
+    /// Set a dotted key in the repo (or --global) config file
+    Set { key: String, value: String },
+    /// Remove a dotted key from the repo (or --global) config file
+    Unset { key: String },
+    /// Print the JSON Schema for config.toml
+    Schema,
+    /// Rewrite the repo (or --global) config file to the current layout
+    Migrate {
+        /// Show the changes without writing them
+        #[arg(short, long)]
+        dry_run: bool,
+    },
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
@@ -112,6 +204,8 @@ async fn main() -> anyhow::Result<()> {
         .with_env_filter(log_level)
         .init();
 
+    config::set_cli_overrides(cli.config_overrides);
+
     match cli.command {
         Commands::Init { force } => {
             cli::init::run(force).await?;
@@ -119,24 +213,33 @@ async fn main() -> anyhow::Result<()> {
         Commands::Enable { events } => {
             cli::enable::run(events).await?;
         }
-        Commands::Disable { keep_config } => {
-            cli::disable::run(keep_config).await?;
+        Commands::Disable { keep_config, purge } => {
+            cli::disable::run(keep_config, purge).await?;
         }
-        Commands::Config { global, edit } => {
-            cli::config::run(global, edit).await?;
+        Commands::Config { global, edit, origin, action } => {
+            cli::config::run(global, edit, origin, action).await?;
         }
         Commands::Status { verbose } => {
             cli::status::run(verbose).await?;
         }
+        Commands::Doctor => {
+            cli::doctor::run().await?;
+        }
         Commands::Logs { count, event } => {
             cli::logs::run(count, event).await?;
         }
-        Commands::Run { event, dry_run } => {
-            cli::run::run(&event, dry_run).await?;
+        Commands::Run { event, dry_run, profile, from_hook, deferred, blocking, args } => {
+            cli::run::run(&event, dry_run, profile.as_deref(), from_hook, deferred, blocking, &args).await?;
+        }
+        Commands::ReleaseNotes { tag, dry_run, profile } => {
+            cli::release_notes::run(&tag, dry_run, profile.as_deref()).await?;
         }
         Commands::Templates { action } => {
             cli::templates::run(action).await?;
         }
+        Commands::Hooks { action } => {
+            cli::hooks::run(action).await?;
+        }
     }
 
     Ok(())
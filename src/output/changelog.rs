@@ -0,0 +1,53 @@
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+use crate::config::ChangelogConfig;
+
+/// Write release notes for `tag` into the changelog as a draft.
+///
+/// The draft is wrapped in marker comments so running again for the same
+/// tag replaces it. A new draft goes above the newest release (the first
+/// `## ` heading or earlier draft), or at the end when there is none yet.
+pub fn write_changelog_draft(response: &str, tag: &str, repo_root: &Path, config: &ChangelogConfig) -> Result<PathBuf> {
+    let path = repo_root.join(&config.path);
+    let existing = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => "# Changelog\n".to_string(),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    };
+
+    let start = format!("<!-- gitclaude:release-notes {} -->", tag);
+    let end = format!("<!-- /gitclaude:release-notes {} -->", tag);
+    let block = format!("{}\n{}\n{}\n", start, response.trim(), end);
+
+    let content = match (existing.find(&start), existing.find(&end)) {
+        (Some(from), Some(to)) if from < to => {
+            let to = to + end.len();
+            let to = if existing[to..].starts_with('\n') { to + 1 } else { to };
+            format!("{}{}{}", &existing[..from], block, &existing[to..])
+        }
+        _ => match first_release_heading(&existing) {
+            Some(at) => format!("{}{}\n{}", &existing[..at], block, &existing[at..]),
+            None => format!("{}\n\n{}", existing.trim_end(), block),
+        },
+    };
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, content).with_context(|| format!("Failed to write {}", path.display()))?;
+
+    Ok(path)
+}
+
+/// Byte offset of the first `## ` line or draft
+fn first_release_heading(content: &str) -> Option<usize> {
+    let mut offset = 0;
+    for line in content.split_inclusive('\n') {
+        if line.starts_with("## ") || line.starts_with("<!-- gitclaude:release-notes ") {
+            return Some(offset);
+        }
+        offset += line.len();
+    }
+    None
+}
@@ -0,0 +1,46 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+
+use crate::config::CommitMsgConfig;
+
+/// Write a drafted commit message into the file git passes to
+/// prepare-commit-msg.
+///
+/// In `comment` mode the draft is added as comment lines under an empty
+/// message, so it is only kept if copied out of them. In `message` mode it
+/// becomes the message, ahead of whatever git already put in the file.
+pub fn write_commit_msg(response: &str, message_file: &Path, repo_root: &Path, config: &CommitMsgConfig) -> Result<()> {
+    let existing = std::fs::read_to_string(message_file)
+        .with_context(|| format!("Failed to read {}", message_file.display()))?;
+    let draft = response.trim();
+
+    let content = if config.mode == "message" {
+        format!("{}\n{}", draft, existing)
+    } else {
+        let comment = comment_char(repo_root);
+        let mut content = format!("\n{} Suggested by gitclaude:\n{}\n", comment, comment);
+        for line in draft.lines() {
+            if line.is_empty() {
+                content.push_str(&format!("{}\n", comment));
+            } else {
+                content.push_str(&format!("{} {}\n", comment, line));
+            }
+        }
+        content.push_str(&format!("{}\n", comment));
+        content.push_str(existing.trim_start_matches('\n'));
+        content
+    };
+
+    std::fs::write(message_file, content)
+        .with_context(|| format!("Failed to write {}", message_file.display()))
+}
+
+/// The character git strips comment lines by, `core.commentChar`
+fn comment_char(repo_root: &Path) -> String {
+    git2::Repository::open(repo_root)
+        .and_then(|repo| repo.config())
+        .and_then(|config| config.get_string("core.commentChar"))
+        .ok()
+        .filter(|c| !c.is_empty() && c != "auto")
+        .unwrap_or_else(|| "#".to_string())
+}
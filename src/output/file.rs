@@ -1,4 +1,5 @@
 use anyhow::Result;
+use std::cmp::Reverse;
 use std::path::Path;
 
 use crate::config::FileOutputConfig;
@@ -46,3 +47,63 @@ pub fn save_response(
 
     Ok(filepath)
 }
+
+/// Copy saved responses for commit `old_hash` so they also belong to
+/// `new_hash`. Returns how many files were copied.
+pub fn copy_responses(repo_root: &Path, config: &FileOutputConfig, old_hash: &str, new_hash: &str) -> Result<usize> {
+    let output_dir = repo_root.join(&config.path);
+    if !output_dir.is_dir() {
+        return Ok(0);
+    }
+
+    let mut copied = 0;
+
+    for entry in std::fs::read_dir(&output_dir)? {
+        let path = entry?.path();
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else { continue };
+        let Some(pos) = hash_position(name, old_hash) else { continue };
+
+        let renamed = format!("{}{}{}", &name[..pos], new_hash, &name[pos + old_hash.len()..]);
+        let target = output_dir.join(renamed);
+        if !target.exists() {
+            std::fs::copy(&path, &target)?;
+            copied += 1;
+        }
+    }
+
+    Ok(copied)
+}
+
+/// Saved responses for commit `hash`, newest first
+pub fn find_responses(repo_root: &Path, config: &FileOutputConfig, hash: &str) -> Result<Vec<std::path::PathBuf>> {
+    let output_dir = repo_root.join(&config.path);
+    if !output_dir.is_dir() {
+        return Ok(vec![]);
+    }
+
+    let mut found = vec![];
+
+    for entry in std::fs::read_dir(&output_dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else { continue };
+        if hash_position(name, hash).is_none() {
+            continue;
+        }
+
+        let modified = entry.metadata()?.modified()?;
+        found.push((modified, path));
+    }
+
+    found.sort_by_key(|entry| Reverse(entry.0));
+    Ok(found.into_iter().map(|(_, path)| path).collect())
+}
+
+/// Where `hash` starts in a response file name. Names are
+/// `<event>_<hash>[_<timestamp>].<ext>`, so the hash must follow a `_` and
+/// end at a `_` or `.`
+fn hash_position(name: &str, hash: &str) -> Option<usize> {
+    let tag = format!("_{}", hash);
+    let pos = name.find(&tag)?;
+    name[pos + tag.len()..].starts_with(['_', '.']).then_some(pos + 1)
+}
@@ -0,0 +1,46 @@
+use anyhow::{Context, Result};
+use git2::{Oid, Repository, Signature};
+use std::path::Path;
+
+use crate::config::GitNoteConfig;
+
+/// Attach the response to a commit as a git note
+pub fn add_git_note(response: &str, commit_hash: &str, repo_root: &Path, config: &GitNoteConfig) -> Result<()> {
+    let repo = Repository::open(repo_root)?;
+    let commit = repo
+        .revparse_single(commit_hash)
+        .and_then(|o| o.peel_to_commit())
+        .with_context(|| format!("Commit {} not found", commit_hash))?;
+
+    let signature = signature(&repo)?;
+    repo.note(&signature, &signature, Some(&config.note_ref), commit.id(), response, true)?;
+
+    Ok(())
+}
+
+/// Copy the note on `from` to `to`, unless `to` already has one.
+/// Returns whether a note was copied.
+pub fn copy_git_note(repo: &Repository, from: Oid, to: Oid, config: &GitNoteConfig) -> Result<bool> {
+    let Ok(note) = repo.find_note(Some(&config.note_ref), from) else {
+        return Ok(false);
+    };
+    if repo.find_note(Some(&config.note_ref), to).is_ok() {
+        return Ok(false);
+    }
+
+    let Some(message) = note.message() else {
+        return Ok(false);
+    };
+
+    let signature = signature(repo)?;
+    repo.note(&signature, &signature, Some(&config.note_ref), to, message, false)?;
+
+    Ok(true)
+}
+
+/// The user's identity, or a gitclaude one when `user.name` isn't set
+fn signature(repo: &Repository) -> Result<Signature<'static>> {
+    repo.signature()
+        .or_else(|_| Signature::now("gitclaude", "gitclaude@localhost"))
+        .context("Failed to create signature for note")
+}
@@ -2,8 +2,14 @@
 
 mod notify;
 mod file;
+mod git_note;
+mod commit_msg;
+mod changelog;
 mod terminal;
 
 pub use notify::*;
 pub use file::*;
+pub use git_note::*;
+pub use commit_msg::*;
+pub use changelog::*;
 pub use terminal::*;
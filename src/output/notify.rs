@@ -21,6 +21,19 @@ pub fn send_notification(title: &str, body: &str, config: &NotifyConfig) -> Resu
     Ok(())
 }
 
+/// Name and version of the notification daemon that shows notifications
+#[cfg(all(unix, not(target_os = "macos")))]
+pub fn notification_server() -> Result<String> {
+    let info = notify_rust::get_server_information()?;
+    Ok(format!("{} {}", info.name, info.version))
+}
+
+/// macOS and Windows always have a notification center
+#[cfg(not(all(unix, not(target_os = "macos"))))]
+pub fn notification_server() -> Result<String> {
+    Ok("system notification center".to_string())
+}
+
 /// Send a notification with the Claude response
 pub fn notify_response(response: &str, event: &str, config: &NotifyConfig) -> Result<()> {
     let title = format!("gitclaude - {}", event);
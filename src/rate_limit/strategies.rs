@@ -1,5 +1,5 @@
 use anyhow::Result;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime};
 
 use crate::config::RateLimitConfig;
@@ -17,9 +17,14 @@ pub enum RateLimitDecision {
     Batch,
 }
 
-/// Check if we should run based on rate limiting config
-pub fn should_run(config: &RateLimitConfig, repo_root: &Path) -> Result<RateLimitDecision> {
-    let state = load_state(repo_root)?;
+/// Bucket used when an event has no separate actions
+pub const DEFAULT_BUCKET: &str = "default";
+
+/// Check if we should run based on rate limiting config.
+///
+/// Each bucket keeps its own state, so actions don't throttle each other.
+pub fn should_run(config: &RateLimitConfig, repo_root: &Path, bucket: &str) -> Result<RateLimitDecision> {
+    let state = load_state(repo_root, bucket)?;
 
     match config.strategy.as_str() {
         "debounce" => check_debounce(config, &state),
@@ -31,8 +36,8 @@ pub fn should_run(config: &RateLimitConfig, repo_root: &Path) -> Result<RateLimi
 }
 
 /// Record that we ran Claude
-pub fn record_run(repo_root: &Path) -> Result<()> {
-    let state_file = state_file_path(repo_root);
+pub fn record_run(repo_root: &Path, bucket: &str) -> Result<()> {
+    let state_file = state_file_path(repo_root, bucket)?;
 
     let state = RateLimitState {
         last_run: Some(SystemTime::now()),
@@ -40,12 +45,42 @@ pub fn record_run(repo_root: &Path) -> Result<()> {
         pending_batch: vec![],
     };
 
+    if let Some(parent) = state_file.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
     let content = serde_json::to_string(&state)?;
     std::fs::write(state_file, content)?;
 
     Ok(())
 }
 
+/// Every rate-limit state file of the repo, with the error reading it if it
+/// can't be. A broken state file makes every run of the repo fail
+pub fn check_state_files(repo_root: &Path) -> Result<Vec<(PathBuf, Result<()>)>> {
+    let state_dir = crate::config::state_dir(repo_root)?;
+    if !state_dir.is_dir() {
+        return Ok(vec![]);
+    }
+
+    let mut files = vec![];
+    for entry in std::fs::read_dir(&state_dir)? {
+        let path = entry?.path();
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else { continue };
+        if !(name == "state.json" || (name.starts_with("state-") && name.ends_with(".json"))) {
+            continue;
+        }
+
+        let result = std::fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|content| Ok(serde_json::from_str::<RateLimitState>(&content).map(|_| ())?));
+        files.push((path, result));
+    }
+
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(files)
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, Default)]
 struct RateLimitState {
     last_run: Option<SystemTime>,
@@ -53,8 +88,8 @@ struct RateLimitState {
     pending_batch: Vec<String>,
 }
 
-fn load_state(repo_root: &Path) -> Result<RateLimitState> {
-    let state_file = state_file_path(repo_root);
+fn load_state(repo_root: &Path, bucket: &str) -> Result<RateLimitState> {
+    let state_file = state_file_path(repo_root, bucket)?;
 
     if !state_file.exists() {
         return Ok(RateLimitState::default());
@@ -66,8 +101,19 @@ fn load_state(repo_root: &Path) -> Result<RateLimitState> {
     Ok(state)
 }
 
-fn state_file_path(repo_root: &Path) -> std::path::PathBuf {
-    repo_root.join(".gitclaude").join(".state.json")
+/// State lives in the common git dir so all worktrees share one limit
+fn state_file_path(repo_root: &Path, bucket: &str) -> Result<std::path::PathBuf> {
+    let name = if bucket == DEFAULT_BUCKET {
+        "state.json".to_string()
+    } else {
+        let bucket: String = bucket
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '-' })
+            .collect();
+        format!("state-{}.json", bucket)
+    };
+
+    Ok(crate::config::state_dir(repo_root)?.join(name))
 }
 
 fn check_debounce(config: &RateLimitConfig, state: &RateLimitState) -> Result<RateLimitDecision> {
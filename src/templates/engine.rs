@@ -12,6 +12,8 @@ pub fn render_template(
     config: &TemplatesConfig,
 ) -> Result<String> {
     let mut handlebars = Handlebars::new();
+    // Prompts are plain text, diffs must reach Claude unescaped
+    handlebars.register_escape_fn(handlebars::no_escape);
 
     // Try to load custom template first
     let template_content = load_template(template_name, config)?;
@@ -43,6 +45,19 @@ fn load_template(name: &str, config: &TemplatesConfig) -> Result<String> {
     anyhow::bail!("Template '{}' not found", name)
 }
 
+/// Check that a template resolves and compiles, without rendering it
+pub fn check_template(name: &str, config: &TemplatesConfig) -> Result<()> {
+    if !template_exists(name, config) {
+        anyhow::bail!("Template '{}' not found", name);
+    }
+
+    let content = load_template(name, config)?;
+    Handlebars::new()
+        .register_template_string(name, &content)
+        .with_context(|| format!("Template '{}' does not compile", name))?;
+    Ok(())
+}
+
 fn expand_path(path: &Path) -> std::path::PathBuf {
     let path_str = path.to_string_lossy();
     if path_str.starts_with("~/") {
@@ -53,12 +68,25 @@ fn expand_path(path: &Path) -> std::path::PathBuf {
     path.to_path_buf()
 }
 
+/// Names of the templates shipped with gitclaude
+pub const BUILTIN_TEMPLATES: &[&str] = &["review", "changelog", "validate", "summary", "rewrite", "commit-message", "context", "release-notes"];
+
+/// Check whether a template resolves to a custom file or a built-in
+pub fn template_exists(name: &str, config: &TemplatesConfig) -> bool {
+    let custom_path = expand_path(&config.directory).join(format!("{}.md", name));
+    custom_path.exists() || (config.fallback_builtin && BUILTIN_TEMPLATES.contains(&name))
+}
+
 fn get_builtin_template(name: &str) -> &'static str {
     match name {
         "review" => include_str!("../../templates/review.md"),
         "changelog" => include_str!("../../templates/changelog.md"),
         "validate" => include_str!("../../templates/validate.md"),
         "summary" => include_str!("../../templates/summary.md"),
+        "rewrite" => include_str!("../../templates/rewrite.md"),
+        "commit-message" => include_str!("../../templates/commit-message.md"),
+        "context" => include_str!("../../templates/context.md"),
+        "release-notes" => include_str!("../../templates/release-notes.md"),
         _ => "# Unknown Template\n\n{{commit_message}}\n\n{{diff}}",
     }
 }
@@ -84,17 +112,52 @@ fn build_template_data(context: &GitContext) -> serde_json::Value {
                 "date": c.date,
             })
         }).collect::<Vec<_>>(),
+        "commit_count": context.commit_count,
+        "source_branch": context.merge.as_ref().map(|m| m.source_branch.as_str()),
+        "target_branch": context.merge.as_ref().map(|m| m.target_branch.as_str()),
+        "squash": context.merge.as_ref().is_some_and(|m| m.squash),
+        "fast_forward": context.merge.as_ref().is_some_and(|m| m.fast_forward),
+        "base_branch": context.checkout.as_ref().and_then(|c| c.base_branch.as_deref()),
+        "authors": context.checkout.as_ref().map(|c| {
+            c.authors.iter().map(|(name, commits)| serde_json::json!({
+                "name": name,
+                "commits": commits,
+            })).collect::<Vec<_>>()
+        }).unwrap_or_default(),
+        "todos": context.checkout.as_ref().map(|c| c.todos.clone()).unwrap_or_default(),
+        "reviews": context.checkout.as_ref().map(|c| {
+            c.reviews.iter().map(|r| serde_json::json!({
+                "hash": r.hash,
+                "content": r.content,
+            })).collect::<Vec<_>>()
+        }).unwrap_or_default(),
+        "tag": context.release.as_ref().map(|r| r.tag.as_str()),
+        "previous_tag": context.release.as_ref().and_then(|r| r.previous_tag.as_deref()),
+        "groups": context.release.as_ref().map(|r| {
+            r.groups.iter().map(|(title, commits)| serde_json::json!({
+                "title": title,
+                "commits": commits.iter().map(|c| serde_json::json!({
+                    "hash": c.hash,
+                    "message": c.message,
+                    "author": c.author,
+                })).collect::<Vec<_>>(),
+            })).collect::<Vec<_>>()
+        }).unwrap_or_default(),
+        "commits": context.commits.iter().map(|c| {
+            serde_json::json!({
+                "hash": c.hash,
+                "message": c.message,
+                "author": c.author,
+                "date": c.date,
+                "diff": c.diff,
+            })
+        }).collect::<Vec<_>>(),
     })
 }
 
 /// List available templates
 pub fn list_templates(config: &TemplatesConfig) -> Result<Vec<String>> {
-    let mut templates = vec![
-        "review".to_string(),
-        "changelog".to_string(),
-        "validate".to_string(),
-        "summary".to_string(),
-    ];
+    let mut templates: Vec<String> = BUILTIN_TEMPLATES.iter().map(|t| t.to_string()).collect();
 
     // Add custom templates
     let custom_dir = expand_path(&config.directory);